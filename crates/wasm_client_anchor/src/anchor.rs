@@ -29,9 +29,13 @@ use wallet_standard::WalletError;
 use wallet_standard::prelude::*;
 use wasm_client_solana::ClientError;
 use wasm_client_solana::ClientWebSocketError;
+use wasm_client_solana::ComputeUnitLimitConfig;
+use wasm_client_solana::PriorityFeeConfig;
 use wasm_client_solana::RpcError;
 use wasm_client_solana::SimulateTransactionResponse;
 use wasm_client_solana::SolanaRpcClient;
+use wasm_client_solana::has_compute_unit_limit;
+use wasm_client_solana::has_compute_unit_price;
 use wasm_client_solana::prelude::*;
 use wasm_client_solana::rpc_config::LogsSubscribeRequest;
 use wasm_client_solana::rpc_config::RpcSimulateTransactionConfig;
@@ -149,6 +153,8 @@ pub type AnchorRequestBuilderPartial<'a, W> = AnchorRequestBuilder<
 		(Vec<AddressLookupTableAccount>,),
 		(),
 		(),
+		(),
+		(),
 	),
 >;
 
@@ -229,6 +235,14 @@ pub struct AnchorRequest<'a, W: WalletAnchor + 'a> {
 	/// Additional options to use when signing the transaction.
 	#[builder(default)]
 	pub options: SolanaSignAndSendTransactionOptions,
+	/// Estimate the priority fee from the recent prioritization fees and
+	/// prepend a compute unit price instruction to the transaction.
+	#[builder(default, setter(strip_option))]
+	pub priority_fee: Option<PriorityFeeConfig>,
+	/// Size the compute unit limit of the transaction from a simulation when
+	/// it is signed and sent.
+	#[builder(default, setter(strip_option))]
+	pub compute_unit_limit: Option<ComputeUnitLimitConfig>,
 }
 
 #[async_trait(?Send)]
@@ -237,6 +251,14 @@ impl<'a, W: WalletAnchor + 'a> AnchorRequestMethods<'a, W> for AnchorRequest<'a,
 		self.options.clone()
 	}
 
+	fn priority_fee(&self) -> Option<PriorityFeeConfig> {
+		self.priority_fee
+	}
+
+	fn compute_unit_limit(&self) -> Option<ComputeUnitLimitConfig> {
+		self.compute_unit_limit
+	}
+
 	fn wallet(&self) -> &'a W {
 		self.wallet
 	}
@@ -287,6 +309,8 @@ pub type EmptyAnchorRequestBuilderPartial<'a, W> = EmptyAnchorRequestBuilder<
 		(Vec<AddressLookupTableAccount>,),
 		(),
 		(),
+		(),
+		(),
 	),
 >;
 
@@ -350,6 +374,14 @@ pub struct EmptyAnchorRequest<'a, W: WalletAnchor + 'a> {
 	pub blockhash: Option<Hash>,
 	#[builder(default)]
 	pub options: SolanaSignAndSendTransactionOptions,
+	/// Estimate the priority fee from the recent prioritization fees and
+	/// prepend a compute unit price instruction to the transaction.
+	#[builder(default, setter(strip_option))]
+	pub priority_fee: Option<PriorityFeeConfig>,
+	/// Size the compute unit limit of the transaction from a simulation when
+	/// it is signed and sent.
+	#[builder(default, setter(strip_option))]
+	pub compute_unit_limit: Option<ComputeUnitLimitConfig>,
 }
 
 #[async_trait(?Send)]
@@ -358,6 +390,14 @@ impl<'a, W: WalletAnchor + 'a> AnchorRequestMethods<'a, W> for EmptyAnchorReques
 		self.options.clone()
 	}
 
+	fn priority_fee(&self) -> Option<PriorityFeeConfig> {
+		self.priority_fee
+	}
+
+	fn compute_unit_limit(&self) -> Option<ComputeUnitLimitConfig> {
+		self.compute_unit_limit
+	}
+
 	fn wallet(&self) -> &'a W {
 		self.wallet
 	}
@@ -403,6 +443,19 @@ pub trait AnchorRequestMethods<'a, W: WalletAnchor + 'a> {
 	fn address_lookup_tables(&self) -> Vec<AddressLookupTableAccount>;
 	/// Get the blockhash to use for this request.
 	async fn blockhash(&self) -> AnchorClientResult<Hash>;
+	/// The configuration used to estimate the priority fee of the
+	/// transaction. No compute unit price instruction is added when this is
+	/// `None`.
+	fn priority_fee(&self) -> Option<PriorityFeeConfig> {
+		None
+	}
+	/// The configuration used to size the compute unit limit of the
+	/// transaction from a simulation in
+	/// [`AnchorRequestMethods::sign_transaction`] and
+	/// [`AnchorRequestMethods::sign_and_send_transaction`].
+	fn compute_unit_limit(&self) -> Option<ComputeUnitLimitConfig> {
+		None
+	}
 
 	/// Get the unsigned message with all the instructions and the current hash.
 	fn message(&self, hash: Hash) -> AnchorClientResult<VersionedMessage> {
@@ -432,23 +485,74 @@ pub trait AnchorRequestMethods<'a, W: WalletAnchor + 'a> {
 
 	/// Get the unsigned [`VersionedTransaction`] with additional instructions
 	/// inserted at the beginning.
+	///
+	/// A compute unit price instruction is prepended when
+	/// [`AnchorRequestMethods::priority_fee`] is configured and the
+	/// instructions don't already set a price.
 	async fn transaction_with_instructions(
 		&self,
 		instructions: &[Instruction],
 	) -> AnchorClientResult<VersionedTransaction> {
 		let hash = self.blockhash().await?;
+		let mut instructions = instructions.to_vec();
+
+		if let Some(config) = self.priority_fee() {
+			let all_instructions = [instructions.as_slice(), &self.instructions()].concat();
+
+			if !has_compute_unit_price(&all_instructions) {
+				let price = self
+					.rpc()
+					.estimate_priority_fee(&all_instructions, config)
+					.await?;
+				instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(price));
+			}
+		}
+
 		let transaction = self
-			.message_with_instructions(hash, instructions)?
+			.message_with_instructions(hash, &instructions)?
 			.into_versioned_transaction();
 
 		Ok(transaction)
 	}
 
+	/// Get the unsigned [`VersionedTransaction`] with the compute unit limit
+	/// sized from a simulation. The transaction is unchanged when the
+	/// instructions already set a compute unit limit.
+	async fn transaction_with_compute_unit_limit(
+		&self,
+		config: ComputeUnitLimitConfig,
+	) -> AnchorClientResult<VersionedTransaction> {
+		let instructions = self.instructions();
+
+		if has_compute_unit_limit(&instructions) {
+			return self.transaction().await;
+		}
+
+		let payer = self.wallet().solana_pubkey();
+		let units = self
+			.rpc()
+			.estimate_compute_unit_limit(
+				&payer,
+				&instructions,
+				&self.address_lookup_tables(),
+				config,
+			)
+			.await?;
+
+		self.transaction_with_instructions(&[ComputeBudgetInstruction::set_compute_unit_limit(
+			units,
+		)])
+		.await
+	}
+
 	/// Sign the transaction with the provided signers using the provided
 	/// [`AnchorRequestMethods::wallet`].
 	async fn sign_transaction(&self) -> AnchorClientResult<VersionedTransaction> {
 		let signers = self.signers();
-		let mut transaction = self.transaction().await?;
+		let mut transaction = match self.compute_unit_limit() {
+			Some(config) => self.transaction_with_compute_unit_limit(config).await?,
+			None => self.transaction().await?,
+		};
 
 		// sign the transaction with local signers.
 		transaction.try_sign(&signers, None)?;
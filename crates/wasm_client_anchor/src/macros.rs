@@ -82,6 +82,12 @@ macro_rules! base_create_request_builder {
 				/// Options to be passed into the transaction being signed or sent.
 				#[builder(default)]
 				pub options: $crate::__private::wallet_standard::SolanaSignAndSendTransactionOptions,
+				/// Estimate the priority fee from the recent prioritization fees and prepend a compute unit price instruction to the transaction.
+				#[builder(default, setter(strip_option))]
+				pub priority_fee: ::core::option::Option<$crate::__private::wasm_client_solana::PriorityFeeConfig>,
+				/// Size the compute unit limit of the transaction from a simulation when it is signed and sent.
+				#[builder(default, setter(strip_option))]
+				pub compute_unit_limit: ::core::option::Option<$crate::__private::wasm_client_solana::ComputeUnitLimitConfig>,
 			}
 
 			#[$crate::__private::async_trait::async_trait(?Send)]
@@ -92,6 +98,14 @@ macro_rules! base_create_request_builder {
 					self.options.clone()
 				}
 
+				fn priority_fee(&self) -> ::core::option::Option<$crate::__private::wasm_client_solana::PriorityFeeConfig> {
+					self.priority_fee
+				}
+
+				fn compute_unit_limit(&self) -> ::core::option::Option<$crate::__private::wasm_client_solana::ComputeUnitLimitConfig> {
+					self.compute_unit_limit
+				}
+
 				fn wallet(&self) -> &'a W {
 					self.wallet
 				}
@@ -175,6 +189,8 @@ macro_rules! create_request_builder {
 						(std::vec::Vec<$crate::__private::solana_sdk::address_lookup_table::AddressLookupTableAccount>,),
 						(),
 						(),
+						(),
+						(),
 					),
 				>;
 			impl<W: $crate::WalletAnchor> $program_struct<W> {
@@ -219,6 +235,8 @@ macro_rules! create_request_builder {
 						(std::vec::Vec<$crate::__private::solana_sdk::address_lookup_table::AddressLookupTableAccount>,),
 						(),
 						(),
+						(),
+						(),
 					),
 				>;
 
@@ -0,0 +1,391 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::task::Context;
+use std::task::Poll;
+
+use futures::Stream;
+use futures::StreamExt;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::GetAccountInfoRequest;
+use crate::GetAccountInfoResponse;
+use crate::SolanaRpcClient;
+use crate::Subscription;
+use crate::SubscriptionId;
+use crate::Unsubscription;
+use crate::rpc_config::RpcAccountInfoConfig;
+use crate::solana_account_decoder::UiAccountEncoding;
+
+type Entries = Arc<Mutex<HashMap<Pubkey, StoreEntry>>>;
+
+struct StoreEntry {
+	/// The latest known state of the account.
+	account: Option<Account>,
+	/// The `id` used to create the subscription shared by every
+	/// [`AccountWatch`] of the account.
+	id: u32,
+	subscription_id: SubscriptionId,
+	/// The number of [`AccountWatch`] handles for the account.
+	consumers: usize,
+}
+
+/// A client side store of accounts which are kept up to date with
+/// `accountSubscribe`.
+///
+/// Each account is seeded with `getMultipleAccounts` and shares a single
+/// websocket subscription between every [`AccountWatch`]. The subscription is
+/// removed once the last [`AccountWatch`] of the account is dropped.
+///
+/// ```
+/// use futures::StreamExt;
+/// use solana_sdk::pubkey::Pubkey;
+/// use wasm_client_solana::AccountStore;
+/// use wasm_client_solana::SolanaRpcClient;
+/// # use wasm_client_solana::ClientResult;
+///
+/// # async fn run(rpc: SolanaRpcClient, mint: Pubkey) -> ClientResult<()> {
+/// let store = AccountStore::new(&rpc);
+/// let mut watch = store.watch(mint).await?;
+///
+/// // the current state is yielded first followed by every update
+/// while let Some(account) = watch.next().await {
+/// 	log::info!("lamports: {:?}", account.map(|account| account.lamports));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AccountStore {
+	rpc: SolanaRpcClient,
+	commitment_config: CommitmentConfig,
+	entries: Entries,
+}
+
+impl AccountStore {
+	/// Create a store which uses the commitment of the `rpc`.
+	pub fn new(rpc: &SolanaRpcClient) -> Self {
+		Self::new_with_commitment(rpc, rpc.commitment_config())
+	}
+
+	pub fn new_with_commitment(rpc: &SolanaRpcClient, commitment_config: CommitmentConfig) -> Self {
+		Self {
+			rpc: rpc.clone(),
+			commitment_config,
+			entries: Arc::default(),
+		}
+	}
+
+	/// The latest known state of the account. Returns `None` when the account
+	/// isn't watched and `Some(None)` when the account doesn't exist.
+	pub fn get(&self, pubkey: &Pubkey) -> Option<Option<Account>> {
+		lock(&self.entries)
+			.get(pubkey)
+			.map(|entry| entry.account.clone())
+	}
+
+	/// The accounts which are currently watched.
+	pub fn watched(&self) -> Vec<Pubkey> {
+		lock(&self.entries).keys().copied().collect()
+	}
+
+	/// Watch a single account. See [`AccountStore::watch_multiple`].
+	pub async fn watch(&self, pubkey: Pubkey) -> ClientResult<AccountWatch> {
+		let mut watches = self.watch_multiple(&[pubkey]).await?;
+
+		Ok(watches.remove(0))
+	}
+
+	/// Watch the accounts at the `pubkeys`, returning a watch for each in the
+	/// same order. Accounts which aren't in the store yet are fetched with a
+	/// single `getMultipleAccounts` request and subscribed to.
+	pub async fn watch_multiple(&self, pubkeys: &[Pubkey]) -> ClientResult<Vec<AccountWatch>> {
+		// the subscriptions of the added entries are held until they are moved
+		// into a watch so the connection stays open
+		let mut subscriptions = HashMap::new();
+
+		loop {
+			let missing = {
+				let entries = lock(&self.entries);
+				let mut missing = pubkeys
+					.iter()
+					.filter(|pubkey| !entries.contains_key(pubkey))
+					.copied()
+					.collect::<Vec<_>>();
+				missing.sort_unstable();
+				missing.dedup();
+				missing
+			};
+
+			if !missing.is_empty() {
+				subscriptions.extend(self.add_entries(&missing).await?);
+			}
+
+			// an account can be removed by its last watch being dropped in the
+			// meantime, in which case it is added again
+			if let Some(watches) = self.create_watches(pubkeys, &mut subscriptions) {
+				return Ok(watches);
+			}
+		}
+	}
+
+	/// Subscribe to the `pubkeys` and seed their entries with the current
+	/// state of the accounts. Returns the subscriptions of the added entries.
+	async fn add_entries(
+		&self,
+		pubkeys: &[Pubkey],
+	) -> ClientResult<HashMap<Pubkey, Subscription<GetAccountInfoResponse>>> {
+		// subscribe before fetching so that no update between the two requests
+		// is missed
+		let subscriptions =
+			futures::future::try_join_all(pubkeys.iter().map(|pubkey| self.subscribe(*pubkey)))
+				.await?;
+		let mut accounts = self.fetch(pubkeys).await?;
+		let mut entries = lock(&self.entries);
+		let mut added = HashMap::new();
+
+		for (pubkey, subscription) in pubkeys.iter().zip(subscriptions) {
+			// another watch subscribed to the account in the meantime
+			if entries.contains_key(pubkey) {
+				unsubscribe(subscription.get_unsubscription());
+				continue;
+			}
+
+			entries.insert(
+				*pubkey,
+				StoreEntry {
+					account: accounts.remove(pubkey).flatten(),
+					id: subscription.id(),
+					subscription_id: subscription.subscription_id(),
+					consumers: 0,
+				},
+			);
+			added.insert(*pubkey, subscription);
+		}
+
+		Ok(added)
+	}
+
+	/// Fetch the current state of the `pubkeys`. Accounts which don't exist
+	/// are `None`.
+	async fn fetch(&self, pubkeys: &[Pubkey]) -> ClientResult<HashMap<Pubkey, Option<Account>>> {
+		let accounts = self
+			.rpc
+			.get_multiple_accounts_with_commitment(pubkeys, self.commitment_config)
+			.await?;
+
+		if accounts.len() != pubkeys.len() {
+			return Err(ClientError::Other(format!(
+				"expected {} accounts but received {}",
+				pubkeys.len(),
+				accounts.len()
+			)));
+		}
+
+		Ok(pubkeys.iter().copied().zip(accounts).collect())
+	}
+
+	/// Create a watch for each of the `pubkeys`, moving the held
+	/// `subscriptions` into the watches. Returns `None` when any of the
+	/// accounts isn't in the store.
+	fn create_watches(
+		&self,
+		pubkeys: &[Pubkey],
+		subscriptions: &mut HashMap<Pubkey, Subscription<GetAccountInfoResponse>>,
+	) -> Option<Vec<AccountWatch>> {
+		let mut entries = lock(&self.entries);
+
+		if !pubkeys.iter().all(|pubkey| entries.contains_key(pubkey)) {
+			return None;
+		}
+
+		let watches = pubkeys
+			.iter()
+			.filter_map(|pubkey| {
+				let entry = entries.get_mut(pubkey)?;
+				// every other watch attaches to the connection which is kept open
+				// by the existing watches of the account
+				let subscription = subscriptions.remove(pubkey).unwrap_or_else(|| {
+					Subscription::new(self.rpc.ws(), entry.id, entry.subscription_id)
+				});
+				entry.consumers += 1;
+
+				Some(AccountWatch {
+					pubkey: *pubkey,
+					initial: entry.account.clone(),
+					initial_yielded: false,
+					subscription,
+					entries: self.entries.clone(),
+				})
+			})
+			.collect();
+
+		Some(watches)
+	}
+
+	async fn subscribe(
+		&self,
+		pubkey: Pubkey,
+	) -> ClientResult<Subscription<GetAccountInfoResponse>> {
+		let request = GetAccountInfoRequest::builder()
+			.pubkey(pubkey)
+			.config(RpcAccountInfoConfig {
+				encoding: Some(UiAccountEncoding::Base64),
+				commitment: Some(self.commitment_config),
+				..Default::default()
+			})
+			.build();
+
+		self.rpc.account_subscribe(request).await
+	}
+}
+
+/// A [`Stream`] of the state of an account in the [`AccountStore`]. The
+/// current state is yielded first followed by every update. The value is
+/// `None` while the account doesn't exist.
+#[must_use = "streams do nothing unless polled"]
+pub struct AccountWatch {
+	pubkey: Pubkey,
+	/// The state of the account when the watch was created.
+	initial: Option<Account>,
+	initial_yielded: bool,
+	subscription: Subscription<GetAccountInfoResponse>,
+	entries: Entries,
+}
+
+impl AccountWatch {
+	pub fn pubkey(&self) -> Pubkey {
+		self.pubkey
+	}
+
+	/// The latest known state of the account.
+	pub fn current(&self) -> Option<Account> {
+		lock(&self.entries)
+			.get(&self.pubkey)
+			.and_then(|entry| entry.account.clone())
+	}
+}
+
+impl Stream for AccountWatch {
+	type Item = Option<Account>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		if !self.initial_yielded {
+			self.initial_yielded = true;
+			return Poll::Ready(Some(self.initial.take()));
+		}
+
+		let Some(notification) = futures::ready!(self.subscription.poll_next_unpin(cx)) else {
+			return Poll::Ready(None);
+		};
+		let account = notification
+			.params
+			.result
+			.value
+			.and_then(|account| account.decode::<Account>());
+
+		if let Some(entry) = lock(&self.entries).get_mut(&self.pubkey) {
+			entry.account.clone_from(&account);
+		}
+
+		Poll::Ready(Some(account))
+	}
+}
+
+impl Drop for AccountWatch {
+	fn drop(&mut self) {
+		let mut entries = lock(&self.entries);
+		let Some(entry) = entries.get_mut(&self.pubkey) else {
+			return;
+		};
+
+		entry.consumers = entry.consumers.saturating_sub(1);
+
+		if entry.consumers == 0 {
+			entries.remove(&self.pubkey);
+			unsubscribe(self.subscription.get_unsubscription());
+		}
+	}
+}
+
+fn lock(entries: &Entries) -> MutexGuard<'_, HashMap<Pubkey, StoreEntry>> {
+	entries
+		.lock()
+		.unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Remove the subscription in the background since it can't be awaited while
+/// dropping.
+fn unsubscribe(unsubscription: Unsubscription) {
+	let future = async move {
+		if let Err(error) = unsubscription.run().await {
+			log::debug!("failed to remove account subscription: {error}");
+		}
+	};
+
+	cfg_if::cfg_if! {
+		if #[cfg(feature = "js")] {
+			wasm_bindgen_futures::spawn_local(future);
+		} else if #[cfg(feature = "ssr")] {
+			match tokio::runtime::Handle::try_current() {
+				Ok(handle) => {
+					handle.spawn(future);
+				}
+				Err(error) => log::debug!("unable to remove account subscription: {error}"),
+			}
+		} else {
+			drop(future);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use assert2::check;
+	use serde_json::Value;
+	use serde_json::json;
+
+	use super::*;
+	use crate::test_utils::TestProvider;
+	use crate::test_utils::account_json;
+
+	#[test]
+	fn fetch_accounts_which_do_not_exist() -> anyhow::Result<()> {
+		let missing = Pubkey::new_unique();
+		let existing = Pubkey::new_unique();
+		// responds with an account for every pubkey except the `missing` one
+		let provider = TestProvider::new(move |_, request| {
+			let accounts = request[0]
+				.as_array()
+				.into_iter()
+				.flatten()
+				.map(|pubkey| {
+					if pubkey.as_str() == Some(missing.to_string().as_str()) {
+						Value::Null
+					} else {
+						account_json(1)
+					}
+				})
+				.collect::<Vec<_>>();
+
+			Ok(json!({ "context": { "slot": 1 }, "value": accounts }))
+		});
+		let rpc = Arc::new(provider).rpc();
+		let store = AccountStore::new(&rpc);
+		let accounts = futures::executor::block_on(store.fetch(&[missing, existing]))?;
+
+		check!(accounts.len() == 2);
+		check!(accounts[&missing].is_none());
+		check!(accounts[&existing].as_ref().map(|account| account.lamports) == Some(1));
+
+		Ok(())
+	}
+}
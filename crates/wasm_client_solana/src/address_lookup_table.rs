@@ -0,0 +1,684 @@
+use std::collections::HashSet;
+
+use solana_sdk::account::Account;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::address_lookup_table::instruction::close_lookup_table;
+use solana_sdk::address_lookup_table::instruction::create_lookup_table;
+use solana_sdk::address_lookup_table::instruction::deactivate_lookup_table;
+use solana_sdk::address_lookup_table::instruction::extend_lookup_table;
+use solana_sdk::address_lookup_table::instruction::freeze_lookup_table;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::state::LookupTableStatus;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::message::v0;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::slot_hashes::SlotHashes;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::VersionedTransaction;
+use wallet_standard::WalletSolanaPubkey;
+use wallet_standard::WalletSolanaSignMessage;
+use wallet_standard::WalletSolanaSignTransaction;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::ComputeUnitLimitConfig;
+use crate::MAX_EXHAUSTIVE_LOOKUP_TABLES;
+use crate::MAX_LOOKUP_ADDRESSES_PER_TRANSACTION;
+use crate::MAX_TRANSACTION_SIZE;
+use crate::SolanaRpcClient;
+use crate::VersionedMessageExtension;
+use crate::VersionedTransactionExtension;
+
+/// Initialize a lookup table that can be used with versioned transactions.
+pub async fn initialize_address_lookup_table<
+	P: WalletSolanaSignTransaction + WalletSolanaPubkey,
+	A: WalletSolanaSignMessage + WalletSolanaPubkey,
+>(
+	rpc: &SolanaRpcClient,
+	payer_wallet: &P,
+	authority_signer: &A,
+	addresses: &[Pubkey],
+) -> ClientResult<Pubkey> {
+	let payer = payer_wallet.try_solana_pubkey()?;
+	let authority = authority_signer.try_solana_pubkey()?;
+
+	if addresses.len() > 256 {
+		return Err(ClientError::Other(
+			"Too many addresses passed to to the VersionedTransaction".into(),
+		));
+	}
+
+	let (chunk, remaining) =
+		addresses.split_at(addresses.len().min(MAX_LOOKUP_ADDRESSES_PER_TRANSACTION));
+	let slot = rpc.get_slot().await? - 1;
+	let (lookup_table_instruction, lookup_table_address) =
+		create_lookup_table(payer, authority, slot);
+	let mut instructions = vec![lookup_table_instruction];
+
+	if !chunk.is_empty() {
+		let instruction =
+			extend_lookup_table(lookup_table_address, authority, Some(payer), chunk.into());
+
+		instructions.push(instruction);
+	}
+
+	let mut versioned_transaction = VersionedTransaction::new_unsigned_v0(
+		&payer,
+		&instructions,
+		&[],
+		rpc.get_latest_blockhash().await?,
+	)?
+	.sign_with_wallet(payer_wallet, None)
+	.await?;
+
+	if payer != authority && !chunk.is_empty() {
+		versioned_transaction
+			.sign_async(authority_signer, None)
+			.await?;
+	}
+
+	rpc.send_transaction(&versioned_transaction).await?;
+
+	if remaining.is_empty() {
+		return Ok(lookup_table_address);
+	}
+
+	rpc.wait_for_new_block(1).await?;
+	extend_address_lookup_table(
+		rpc,
+		payer_wallet,
+		authority_signer,
+		&lookup_table_address,
+		remaining,
+	)
+	.await?;
+
+	Ok(lookup_table_address)
+}
+
+/// Add the `addresses` to an existing lookup table. The addresses are split
+/// across as many transactions as needed, each extending the table once with
+/// as many addresses as fit.
+pub async fn extend_address_lookup_table<
+	P: WalletSolanaSignTransaction + WalletSolanaPubkey,
+	A: WalletSolanaSignMessage + WalletSolanaPubkey,
+>(
+	rpc: &SolanaRpcClient,
+	payer_wallet: &P,
+	authority_signer: &A,
+	lookup_table_address: &Pubkey,
+	addresses: &[Pubkey],
+) -> ClientResult<()> {
+	let payer = payer_wallet.try_solana_pubkey()?;
+	let authority = authority_signer.try_solana_pubkey()?;
+
+	if addresses.is_empty() {
+		return Ok(());
+	}
+
+	let chunk = &addresses[..addresses.len().min(MAX_LOOKUP_ADDRESSES_PER_TRANSACTION)];
+	let instruction =
+		extend_lookup_table(*lookup_table_address, authority, Some(payer), chunk.into());
+	let compute_units = rpc
+		.estimate_compute_unit_limit(
+			&payer,
+			&[instruction],
+			&[],
+			ComputeUnitLimitConfig::default(),
+		)
+		.await?;
+
+	let transactions = extend_transactions(
+		lookup_table_address,
+		&authority,
+		&payer,
+		addresses,
+		compute_units,
+	)?;
+
+	for instructions in transactions {
+		send_with_authority(rpc, payer_wallet, authority_signer, &instructions).await?;
+	}
+
+	Ok(())
+}
+
+/// The instructions of each transaction which extends the lookup table with
+/// the `addresses`. The compute unit limit is only set when `compute_units` is
+/// known, otherwise the default limit is used.
+fn extend_transactions(
+	lookup_table_address: &Pubkey,
+	authority: &Pubkey,
+	payer: &Pubkey,
+	addresses: &[Pubkey],
+	compute_units: u32,
+) -> ClientResult<Vec<Vec<Instruction>>> {
+	let mut transactions = vec![];
+	let mut remaining = addresses;
+
+	while !remaining.is_empty() {
+		let mut count = remaining.len().min(MAX_LOOKUP_ADDRESSES_PER_TRANSACTION);
+
+		let instructions = loop {
+			let instruction = extend_lookup_table(
+				*lookup_table_address,
+				*authority,
+				Some(*payer),
+				remaining[..count].into(),
+			);
+			let instructions = if compute_units == 0 {
+				vec![instruction]
+			} else {
+				vec![
+					ComputeBudgetInstruction::set_compute_unit_limit(compute_units),
+					instruction,
+				]
+			};
+			let message = v0::Message::try_compile(payer, &instructions, &[], Hash::default())?;
+
+			// a transaction with a single address is left for the rpc to reject
+			if count == 1
+				|| VersionedMessage::V0(message).transaction_size() <= MAX_TRANSACTION_SIZE
+			{
+				break instructions;
+			}
+
+			count -= 1;
+		};
+
+		transactions.push(instructions);
+		remaining = &remaining[count..];
+	}
+
+	Ok(transactions)
+}
+
+/// Deactivate a lookup table. It can no longer be extended or used by new
+/// transactions and can be closed with [`close_address_lookup_table`] once it
+/// has cooled down.
+pub async fn deactivate_address_lookup_table<
+	P: WalletSolanaSignTransaction + WalletSolanaPubkey,
+	A: WalletSolanaSignMessage + WalletSolanaPubkey,
+>(
+	rpc: &SolanaRpcClient,
+	payer_wallet: &P,
+	authority_signer: &A,
+	lookup_table_address: &Pubkey,
+) -> ClientResult<Signature> {
+	let authority = authority_signer.try_solana_pubkey()?;
+	let instruction = deactivate_lookup_table(*lookup_table_address, authority);
+
+	send_with_authority(rpc, payer_wallet, authority_signer, &[instruction]).await
+}
+
+/// Freeze a lookup table so that its addresses can never change. The table
+/// loses its authority and can no longer be deactivated or closed.
+pub async fn freeze_address_lookup_table<
+	P: WalletSolanaSignTransaction + WalletSolanaPubkey,
+	A: WalletSolanaSignMessage + WalletSolanaPubkey,
+>(
+	rpc: &SolanaRpcClient,
+	payer_wallet: &P,
+	authority_signer: &A,
+	lookup_table_address: &Pubkey,
+) -> ClientResult<Signature> {
+	let authority = authority_signer.try_solana_pubkey()?;
+	let instruction = freeze_lookup_table(*lookup_table_address, authority);
+
+	send_with_authority(rpc, payer_wallet, authority_signer, &[instruction]).await
+}
+
+/// Close a deactivated lookup table and send its lamports to the `recipient`.
+///
+/// An error is returned without sending a transaction when the table is still
+/// active or hasn't finished cooling down after being deactivated.
+pub async fn close_address_lookup_table<
+	P: WalletSolanaSignTransaction + WalletSolanaPubkey,
+	A: WalletSolanaSignMessage + WalletSolanaPubkey,
+>(
+	rpc: &SolanaRpcClient,
+	payer_wallet: &P,
+	authority_signer: &A,
+	lookup_table_address: &Pubkey,
+	recipient: &Pubkey,
+) -> ClientResult<Signature> {
+	match rpc
+		.get_address_lookup_table_status(lookup_table_address)
+		.await?
+	{
+		LookupTableStatus::Deactivated => {}
+		LookupTableStatus::Activated => {
+			return Err(ClientError::Other(format!(
+				"Lookup table {lookup_table_address} must be deactivated before it is closed"
+			)));
+		}
+		LookupTableStatus::Deactivating { remaining_blocks } => {
+			return Err(ClientError::Other(format!(
+				"Lookup table {lookup_table_address} can be closed in {remaining_blocks} blocks"
+			)));
+		}
+	}
+
+	let authority = authority_signer.try_solana_pubkey()?;
+	let instruction = close_lookup_table(*lookup_table_address, authority, *recipient);
+
+	send_with_authority(rpc, payer_wallet, authority_signer, &[instruction]).await
+}
+
+impl SolanaRpcClient {
+	/// Fetch the lookup tables at the `addresses` so they can be used to
+	/// compile versioned transactions. The tables are returned in the same
+	/// order as the `addresses`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if any of the tables doesn't exist or isn't a lookup
+	/// table.
+	pub async fn get_address_lookup_table_accounts(
+		&self,
+		addresses: &[Pubkey],
+	) -> ClientResult<Vec<AddressLookupTableAccount>> {
+		let accounts = self.get_multiple_accounts(addresses).await?;
+
+		addresses
+			.iter()
+			.zip(accounts)
+			.map(|(address, account)| {
+				let account = account.ok_or_else(|| {
+					ClientError::Other(format!("Lookup table {address} not found"))
+				})?;
+
+				lookup_table_account(address, &account)
+			})
+			.collect()
+	}
+
+	/// The activation status of the lookup table at `address`, determined from
+	/// the current slot and the recent slot hashes.
+	pub async fn get_address_lookup_table_status(
+		&self,
+		address: &Pubkey,
+	) -> ClientResult<LookupTableStatus> {
+		let accounts = self
+			.get_multiple_accounts(&[*address, sysvar::slot_hashes::ID])
+			.await?;
+		let [Some(account), Some(slot_hashes_account)] = accounts.as_slice() else {
+			return Err(ClientError::Other(format!(
+				"Lookup table {address} not found"
+			)));
+		};
+		let slot_hashes: SlotHashes = bincode::deserialize(&slot_hashes_account.data)
+			.map_err(|error| ClientError::Other(error.to_string()))?;
+		let table = AddressLookupTable::deserialize(&account.data)
+			.map_err(|error| ClientError::Other(format!("Lookup table {address}: {error}")))?;
+		let current_slot = self.get_slot().await?;
+
+		Ok(table.meta.status(current_slot, &slot_hashes))
+	}
+}
+
+/// A message compiled by [`compile_with_lookup_tables`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledMessage {
+	/// The compiled message.
+	pub message: VersionedMessage,
+	/// The lookup tables used by the message.
+	pub address_lookup_tables: Vec<AddressLookupTableAccount>,
+	/// The accounts which are stored in the message instead of being loaded
+	/// from a lookup table. Signers and invoked programs are excluded since
+	/// they can never be loaded from a lookup table.
+	pub uncompressed_accounts: Vec<Pubkey>,
+}
+
+/// Compile a `v0` message using the subset of the `address_lookup_tables`
+/// which produces the smallest transaction.
+///
+/// Only tables which contain accounts of the `instructions` are considered.
+/// When there are at most [`MAX_EXHAUSTIVE_LOOKUP_TABLES`] of them every
+/// subset is compiled and the smallest message, using the fewest tables, is
+/// returned. With more tables this is a heuristic: the table which shrinks
+/// the transaction the most is added until no remaining table makes it
+/// smaller, which may miss the smallest subset.
+pub fn compile_with_lookup_tables(
+	payer: &Pubkey,
+	instructions: &[Instruction],
+	address_lookup_tables: &[AddressLookupTableAccount],
+	recent_blockhash: Hash,
+) -> ClientResult<CompiledMessage> {
+	let accounts = instructions
+		.iter()
+		.flat_map(|instruction| instruction.accounts.iter().map(|meta| meta.pubkey))
+		.collect::<HashSet<_>>();
+	let candidates = address_lookup_tables
+		.iter()
+		.filter(|table| table.addresses.iter().any(|key| accounts.contains(key)))
+		.cloned()
+		.collect::<Vec<_>>();
+	let compile = |tables: &[AddressLookupTableAccount]| {
+		v0::Message::try_compile(payer, instructions, tables, recent_blockhash)
+			.map(VersionedMessage::V0)
+	};
+	let message = compile(&[])?;
+
+	let (message, address_lookup_tables) = if candidates.len() <= MAX_EXHAUSTIVE_LOOKUP_TABLES {
+		smallest_subset(message, &candidates, compile)
+	} else {
+		greedy_subset(message, candidates, compile)
+	};
+	let uncompressed_accounts = uncompressed_accounts(&message);
+
+	Ok(CompiledMessage {
+		message,
+		address_lookup_tables,
+		uncompressed_accounts,
+	})
+}
+
+/// Compile every subset of the `candidates`, from the fewest tables to the
+/// most, and keep the smallest message.
+fn smallest_subset<E>(
+	message: VersionedMessage,
+	candidates: &[AddressLookupTableAccount],
+	compile: impl Fn(&[AddressLookupTableAccount]) -> Result<VersionedMessage, E>,
+) -> (VersionedMessage, Vec<AddressLookupTableAccount>) {
+	let mut subsets = (1..1u32 << candidates.len()).collect::<Vec<_>>();
+	subsets.sort_by_key(|subset| subset.count_ones());
+
+	let mut size = message.transaction_size();
+	let mut best = (message, vec![]);
+
+	for subset in subsets {
+		let tables = candidates
+			.iter()
+			.enumerate()
+			.filter(|(index, _)| subset & (1 << index) != 0)
+			.map(|(_, table)| table.clone())
+			.collect::<Vec<_>>();
+
+		// tables which can't be compiled, e.g. because of too many accounts,
+		// are skipped
+		let Ok(compiled) = compile(&tables) else {
+			continue;
+		};
+
+		let compiled_size = compiled.transaction_size();
+
+		if compiled_size < size {
+			size = compiled_size;
+			best = (compiled, tables);
+		}
+	}
+
+	best
+}
+
+/// Add the table which shrinks the message the most until no remaining table
+/// makes it smaller.
+fn greedy_subset<E>(
+	mut message: VersionedMessage,
+	mut candidates: Vec<AddressLookupTableAccount>,
+	compile: impl Fn(&[AddressLookupTableAccount]) -> Result<VersionedMessage, E>,
+) -> (VersionedMessage, Vec<AddressLookupTableAccount>) {
+	let mut selected: Vec<AddressLookupTableAccount> = vec![];
+	let mut size = message.transaction_size();
+
+	loop {
+		let mut best: Option<(usize, usize, VersionedMessage)> = None;
+
+		for (index, candidate) in candidates.iter().enumerate() {
+			let tables = [selected.as_slice(), &[candidate.clone()]].concat();
+
+			let Ok(compiled) = compile(&tables) else {
+				continue;
+			};
+
+			let compiled_size = compiled.transaction_size();
+
+			if compiled_size < best.as_ref().map_or(size, |(_, size, _)| *size) {
+				best = Some((index, compiled_size, compiled));
+			}
+		}
+
+		let Some((index, best_size, best_message)) = best else {
+			break;
+		};
+
+		selected.push(candidates.remove(index));
+		message = best_message;
+		size = best_size;
+	}
+
+	(message, selected)
+}
+
+/// The static accounts of the `message` which aren't signers or invoked
+/// programs.
+fn uncompressed_accounts(message: &VersionedMessage) -> Vec<Pubkey> {
+	let signers = usize::from(message.header().num_required_signatures);
+	let programs = message
+		.instructions()
+		.iter()
+		.map(|instruction| usize::from(instruction.program_id_index))
+		.collect::<HashSet<_>>();
+
+	message
+		.static_account_keys()
+		.iter()
+		.enumerate()
+		.skip(signers)
+		.filter(|(index, _)| !programs.contains(index))
+		.map(|(_, key)| *key)
+		.collect()
+}
+
+/// Deserialize the lookup table stored in the `account`.
+fn lookup_table_account(
+	address: &Pubkey,
+	account: &Account,
+) -> ClientResult<AddressLookupTableAccount> {
+	let table = AddressLookupTable::deserialize(&account.data)
+		.map_err(|error| ClientError::Other(format!("Lookup table {address}: {error}")))?;
+
+	Ok(AddressLookupTableAccount {
+		key: *address,
+		addresses: table.addresses.to_vec(),
+	})
+}
+
+/// Send the `instructions` signed by the payer and, when it is a different
+/// account, the lookup table authority.
+async fn send_with_authority<
+	P: WalletSolanaSignTransaction + WalletSolanaPubkey,
+	A: WalletSolanaSignMessage + WalletSolanaPubkey,
+>(
+	rpc: &SolanaRpcClient,
+	payer_wallet: &P,
+	authority_signer: &A,
+	instructions: &[Instruction],
+) -> ClientResult<Signature> {
+	let payer = payer_wallet.try_solana_pubkey()?;
+	let authority = authority_signer.try_solana_pubkey()?;
+	let mut versioned_transaction = VersionedTransaction::new_unsigned_v0(
+		&payer,
+		instructions,
+		&[],
+		rpc.get_latest_blockhash().await?,
+	)?
+	.sign_with_wallet(payer_wallet, None)
+	.await?;
+
+	if payer != authority {
+		versioned_transaction
+			.sign_async(authority_signer, None)
+			.await?;
+	}
+
+	rpc.send_transaction(&versioned_transaction).await
+}
+
+#[cfg(test)]
+mod tests {
+	use std::borrow::Cow;
+
+	use assert2::check;
+	use solana_sdk::address_lookup_table::state::LookupTableMeta;
+	use solana_sdk::instruction::AccountMeta;
+	use solana_sdk::system_instruction::transfer;
+
+	use super::*;
+
+	#[test]
+	fn extend_transactions_fit_in_a_packet() -> anyhow::Result<()> {
+		let lookup_table_address = Pubkey::new_unique();
+		let authority = Pubkey::new_unique();
+		let payer = Pubkey::new_unique();
+		let addresses = (0..100).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+
+		for compute_units in [0, 5_000] {
+			let transactions = extend_transactions(
+				&lookup_table_address,
+				&authority,
+				&payer,
+				&addresses,
+				compute_units,
+			)?;
+			// the data of an extend is the instruction index and the length
+			// followed by the addresses
+			let extended = transactions
+				.iter()
+				.filter_map(|instructions| instructions.last())
+				.map(|instruction| (instruction.data.len() - 12) / 32)
+				.sum::<usize>();
+
+			check!(extended == addresses.len());
+
+			for instructions in transactions {
+				check!(instructions.len() == if compute_units == 0 { 1 } else { 2 });
+
+				let message =
+					v0::Message::try_compile(&payer, &instructions, &[], Hash::default())?;
+				check!(let Ok(_) = VersionedMessage::V0(message).check_transaction_size());
+			}
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn deserialize_lookup_table_account() -> anyhow::Result<()> {
+		let address = Pubkey::new_unique();
+		let addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+		let table = AddressLookupTable {
+			meta: LookupTableMeta::default(),
+			addresses: Cow::Borrowed(&addresses),
+		};
+		let account = Account {
+			data: table.serialize_for_tests()?,
+			owner: solana_sdk::address_lookup_table::program::ID,
+			..Account::default()
+		};
+		let lookup_table = lookup_table_account(&address, &account)?;
+
+		check!(lookup_table.key == address);
+		check!(lookup_table.addresses == addresses);
+
+		let account = Account {
+			data: vec![1, 2, 3],
+			..account
+		};
+		check!(let Err(ClientError::Other(_)) = lookup_table_account(&address, &account));
+
+		Ok(())
+	}
+
+	fn lookup_table(addresses: &[Pubkey]) -> AddressLookupTableAccount {
+		AddressLookupTableAccount {
+			key: Pubkey::new_unique(),
+			addresses: addresses.to_vec(),
+		}
+	}
+
+	#[test]
+	fn selects_smallest_set_of_lookup_tables() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let recipients = (0..20).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+		let uncovered = Pubkey::new_unique();
+		let instructions = recipients
+			.iter()
+			.chain([&uncovered])
+			.map(|recipient| transfer(&payer, recipient, 1))
+			.collect::<Vec<_>>();
+		let useful = lookup_table(&recipients);
+		// only covers one account so it costs more than it saves
+		let single = lookup_table(&[recipients[0]]);
+		let unrelated = lookup_table(&[Pubkey::new_unique()]);
+		let tables = [single.clone(), unrelated, useful.clone()];
+
+		let compiled = compile_with_lookup_tables(&payer, &instructions, &tables, Hash::default())?;
+		let all_tables = VersionedMessage::V0(v0::Message::try_compile(
+			&payer,
+			&instructions,
+			&tables,
+			Hash::default(),
+		)?);
+
+		check!(compiled.address_lookup_tables == vec![useful]);
+		check!(compiled.uncompressed_accounts == vec![uncovered]);
+		check!(compiled.message.transaction_size() < all_tables.transaction_size());
+
+		let compiled =
+			compile_with_lookup_tables(&payer, &instructions, &[single], Hash::default())?;
+
+		check!(compiled.address_lookup_tables.is_empty());
+		check!(compiled.uncompressed_accounts.len() == 21);
+
+		Ok(())
+	}
+
+	#[test]
+	fn finds_smaller_subset_than_greedy_selection() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let accounts = (0..6).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+		let instruction = Instruction::new_with_bytes(
+			Pubkey::new_unique(),
+			&[],
+			accounts
+				.iter()
+				.map(|account| AccountMeta::new_readonly(*account, false))
+				.collect(),
+		);
+		// covers the most accounts so it's chosen first, after which neither
+		// of the other tables saves enough to be added
+		let largest = lookup_table(&accounts[..4]);
+		let first_half = lookup_table(&[accounts[0], accounts[1], accounts[4]]);
+		let second_half = lookup_table(&[accounts[2], accounts[3], accounts[5]]);
+		let tables = [largest.clone(), first_half.clone(), second_half.clone()];
+
+		let compiled =
+			compile_with_lookup_tables(&payer, &[instruction.clone()], &tables, Hash::default())?;
+		let (greedy, greedy_tables) = greedy_subset(
+			VersionedMessage::V0(v0::Message::try_compile(
+				&payer,
+				&[instruction.clone()],
+				&[],
+				Hash::default(),
+			)?),
+			tables.to_vec(),
+			|tables| {
+				v0::Message::try_compile(&payer, &[instruction.clone()], tables, Hash::default())
+					.map(VersionedMessage::V0)
+			},
+		);
+
+		check!(greedy_tables == vec![largest]);
+		check!(compiled.address_lookup_tables == vec![first_half, second_half]);
+		check!(compiled.uncompressed_accounts.is_empty());
+		check!(compiled.message.transaction_size() < greedy.transaction_size());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,152 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::RpcProvider;
+use crate::methods::HttpMethod;
+use crate::solana_client::parse_response;
+
+/// Queue multiple [`HttpMethod`] requests and send them as a single JSON-RPC
+/// batch request with [`RpcBatch::send`].
+///
+/// Each call to [`RpcBatch::add`] returns a [`BatchItem`] which is used to
+/// read the typed response from the [`BatchResponses`].
+#[derive(Clone, derive_more::Debug)]
+pub struct RpcBatch {
+	#[debug(skip)]
+	provider: Arc<dyn RpcProvider + Send + Sync + 'static>,
+	requests: Vec<(&'static str, Value)>,
+}
+
+impl RpcBatch {
+	pub fn new(provider: Arc<dyn RpcProvider + Send + Sync + 'static>) -> Self {
+		Self {
+			provider,
+			requests: vec![],
+		}
+	}
+
+	/// Add a request to the batch. The returned [`BatchItem`] is used to read
+	/// the response once the batch has been sent.
+	pub fn add<T: HttpMethod, R: DeserializeOwned>(
+		&mut self,
+		request: T,
+	) -> ClientResult<BatchItem<R>> {
+		let value =
+			serde_json::to_value(request).map_err(|error| ClientError::Other(error.to_string()))?;
+		let index = self.requests.len();
+		self.requests.push((T::NAME, value));
+
+		Ok(BatchItem::new(index))
+	}
+
+	/// The number of requests in the batch.
+	pub fn len(&self) -> usize {
+		self.requests.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.requests.is_empty()
+	}
+
+	/// Send every request in the batch.
+	pub async fn send(self) -> ClientResult<BatchResponses> {
+		let values = self.provider.send_batch(self.requests).await?;
+
+		Ok(BatchResponses(values))
+	}
+}
+
+/// A reference to a request added to an [`RpcBatch`].
+#[derive(Debug)]
+pub struct BatchItem<R> {
+	index: usize,
+	marker: PhantomData<fn() -> R>,
+}
+
+impl<R> BatchItem<R> {
+	fn new(index: usize) -> Self {
+		Self {
+			index,
+			marker: PhantomData,
+		}
+	}
+
+	/// The position of the request within the batch.
+	pub fn index(&self) -> usize {
+		self.index
+	}
+}
+
+impl<R> Clone for BatchItem<R> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<R> Copy for BatchItem<R> {}
+
+/// The responses of a sent [`RpcBatch`] in the order the requests were added.
+#[derive(Debug, Clone)]
+pub struct BatchResponses(Vec<Value>);
+
+impl BatchResponses {
+	/// Get the typed response for the provided [`BatchItem`]. An error is
+	/// returned if the rpc responded with an error for this request.
+	pub fn get<R: DeserializeOwned>(&self, item: BatchItem<R>) -> ClientResult<R> {
+		let Some(value) = self.0.get(item.index) else {
+			return Err(ClientError::Other(format!(
+				"no response for batch item: {}",
+				item.index
+			)));
+		};
+
+		parse_response(value.clone())
+	}
+
+	/// The raw JSON-RPC responses.
+	pub fn into_values(self) -> Vec<Value> {
+		self.0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use serde_json::json;
+	use solana_sdk::pubkey;
+
+	use super::*;
+	use crate::ClientResponse;
+	use crate::GetBalanceRequest;
+	use crate::GetBalanceResponse;
+	use crate::GetSlotRequest;
+	use crate::test_utils::TestProvider;
+	use crate::test_utils::rpc_error;
+
+	#[test]
+	fn batch_responses() -> anyhow::Result<()> {
+		let provider = TestProvider::new(|method, _| {
+			match method {
+				"getSlot" => Ok(json!(10)),
+				_ => Err(rpc_error(-32602, "Invalid params")),
+			}
+		});
+		let mut batch = RpcBatch::new(Arc::new(provider));
+		let pubkey = pubkey!("83astBRguLMdt2h5U1Tpdq5tjFoJ6noeGwaY3mDLVcri");
+		let balance =
+			batch.add::<_, ClientResponse<GetBalanceResponse>>(GetBalanceRequest::new(pubkey))?;
+		let slot = batch.add::<_, ClientResponse<u64>>(GetSlotRequest::new())?;
+		check!(batch.len() == 2);
+
+		let responses = futures::executor::block_on(batch.send())?;
+		check!(let Err(ClientError::Rpc(_)) = responses.get(balance));
+		check!(responses.get(slot)?.result == 10);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,168 @@
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::compute_budget;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use typed_builder::TypedBuilder;
+
+use crate::COMPUTE_UNIT_MAX_LIMIT;
+use crate::ClientError;
+use crate::ClientResult;
+use crate::SolanaRpcClient;
+use crate::VersionedTransactionExtension;
+
+/// Configure how the compute unit limit is sized from a simulation.
+///
+/// ```
+/// use wasm_client_solana::ComputeUnitLimitConfig;
+///
+/// let config = ComputeUnitLimitConfig::builder().margin_percent(20).build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypedBuilder)]
+pub struct ComputeUnitLimitConfig {
+	/// The percentage added to the simulated compute units to account for
+	/// changes in state between the simulation and the transaction landing.
+	#[builder(default = 10)]
+	pub margin_percent: u32,
+}
+
+impl Default for ComputeUnitLimitConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl ComputeUnitLimitConfig {
+	/// The compute unit limit for a transaction which consumed
+	/// `units_consumed` in simulation.
+	pub fn compute_unit_limit(&self, units_consumed: u64) -> u32 {
+		let units = u128::from(units_consumed) * (100 + u128::from(self.margin_percent)) / 100;
+
+		u32::try_from(units.min(COMPUTE_UNIT_MAX_LIMIT as u128)).unwrap_or(u32::MAX)
+	}
+}
+
+fn compute_unit_limit_discriminator() -> u8 {
+	ComputeBudgetInstruction::set_compute_unit_limit(0).data[0]
+}
+
+fn is_compute_unit_limit(instruction: &Instruction) -> bool {
+	instruction.program_id == compute_budget::id()
+		&& instruction.data.first() == Some(&compute_unit_limit_discriminator())
+}
+
+/// Whether the `instructions` already set a compute unit limit.
+pub fn has_compute_unit_limit(instructions: &[Instruction]) -> bool {
+	instructions.iter().any(is_compute_unit_limit)
+}
+
+/// Replace the compute unit limit set by the `instructions` with `units` or
+/// insert a [`ComputeBudgetInstruction::set_compute_unit_limit`] instruction
+/// at the start when there is none.
+pub fn set_compute_unit_limit(instructions: &[Instruction], units: u32) -> Vec<Instruction> {
+	let limit = ComputeBudgetInstruction::set_compute_unit_limit(units);
+	let mut instructions = instructions.to_vec();
+
+	match instructions.iter_mut().find(|ix| is_compute_unit_limit(ix)) {
+		Some(instruction) => *instruction = limit,
+		None => instructions.insert(0, limit),
+	}
+
+	instructions
+}
+
+impl SolanaRpcClient {
+	/// Simulate the `instructions` with the maximum compute unit limit and
+	/// return the limit required for them, including the margin from the
+	/// `config`.
+	pub async fn estimate_compute_unit_limit(
+		&self,
+		payer: &Pubkey,
+		instructions: &[Instruction],
+		address_lookup_tables: &[AddressLookupTableAccount],
+		config: ComputeUnitLimitConfig,
+	) -> ClientResult<u32> {
+		let instructions = set_compute_unit_limit(instructions, COMPUTE_UNIT_MAX_LIMIT as u32);
+		// the blockhash is replaced by the rpc during simulation
+		let transaction = VersionedTransaction::new_unsigned_v0(
+			payer,
+			&instructions,
+			address_lookup_tables,
+			Hash::default(),
+		)?;
+		let result = self.simulate_transaction(&transaction).await?.value;
+
+		if let Some(error) = result.err {
+			return Err(ClientError::Other(format!(
+				"transaction simulation failed: {error}"
+			)));
+		}
+
+		let Some(units_consumed) = result.units_consumed else {
+			return Err(ClientError::Other(
+				"Could not calculate the optimal compute units".into(),
+			));
+		};
+
+		Ok(config.compute_unit_limit(units_consumed))
+	}
+
+	/// Size the compute unit limit of the `instructions` from a simulation.
+	/// The existing [`ComputeBudgetInstruction::set_compute_unit_limit`]
+	/// instruction is rewritten or a new one is inserted at the start.
+	pub async fn with_compute_unit_limit(
+		&self,
+		payer: &Pubkey,
+		instructions: &[Instruction],
+		address_lookup_tables: &[AddressLookupTableAccount],
+		config: ComputeUnitLimitConfig,
+	) -> ClientResult<Vec<Instruction>> {
+		let units = self
+			.estimate_compute_unit_limit(payer, instructions, address_lookup_tables, config)
+			.await?;
+
+		Ok(set_compute_unit_limit(instructions, units))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+
+	use super::*;
+
+	#[test]
+	fn compute_unit_limit_margin() {
+		let config = ComputeUnitLimitConfig::default();
+
+		check!(config.compute_unit_limit(1_000) == 1_100);
+		check!(config.compute_unit_limit(0) == 0);
+		check!(config.compute_unit_limit(u64::MAX) == COMPUTE_UNIT_MAX_LIMIT as u32);
+	}
+
+	#[test]
+	fn rewrites_existing_limit() {
+		let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+		let instructions = vec![
+			ComputeBudgetInstruction::set_compute_unit_price(1),
+			ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+			instruction.clone(),
+		];
+
+		let result = set_compute_unit_limit(&instructions, 5_000);
+		check!(result.len() == 3);
+		check!(result[1] == ComputeBudgetInstruction::set_compute_unit_limit(5_000));
+
+		let result = set_compute_unit_limit(&[instruction.clone()], 5_000);
+		check!(
+			result
+				== [
+					ComputeBudgetInstruction::set_compute_unit_limit(5_000),
+					instruction
+				]
+		);
+		check!(has_compute_unit_limit(&result));
+	}
+}
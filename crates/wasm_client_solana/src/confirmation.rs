@@ -0,0 +1,131 @@
+use serde::Deserialize;
+use serde::Serialize;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::transaction::TransactionError;
+
+use crate::solana_transaction_status::TransactionStatus;
+
+/// The outcome of waiting for a transaction with
+/// [`SolanaRpcClient::confirm_transaction_with_block_height`](crate::SolanaRpcClient::confirm_transaction_with_block_height).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionConfirmation {
+	/// The transaction reached the requested commitment without errors.
+	Confirmed,
+	/// The transaction was processed but failed.
+	Failed(TransactionError),
+	/// The block height passed the last valid block height of the
+	/// transaction's blockhash before the transaction was confirmed. The
+	/// transaction can no longer be processed and is safe to rebuild with a
+	/// new blockhash.
+	Expired,
+}
+
+impl TransactionConfirmation {
+	pub fn is_confirmed(&self) -> bool {
+		matches!(self, Self::Confirmed)
+	}
+
+	pub fn is_expired(&self) -> bool {
+		matches!(self, Self::Expired)
+	}
+
+	/// The error of a failed transaction.
+	pub fn err(&self) -> Option<&TransactionError> {
+		match self {
+			Self::Failed(error) => Some(error),
+			_ => None,
+		}
+	}
+
+	/// Determine the outcome from the current `status` of the transaction and
+	/// the `block_height` which was read before the status. Returns `None`
+	/// while the transaction is still pending.
+	pub(crate) fn from_status(
+		status: Option<&TransactionStatus>,
+		commitment_config: CommitmentConfig,
+		block_height: u64,
+		last_valid_block_height: u64,
+	) -> Option<Self> {
+		match status {
+			Some(status) if status.satisfies_commitment(commitment_config) => {
+				match &status.err {
+					Some(error) => Some(Self::Failed(error.clone())),
+					None => Some(Self::Confirmed),
+				}
+			}
+			None if block_height > last_valid_block_height => Some(Self::Expired),
+			// a processed transaction can still be confirmed after its
+			// blockhash expires
+			Some(_) | None => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use solana_sdk::instruction::InstructionError;
+
+	use super::*;
+	use crate::solana_transaction_status::TransactionConfirmationStatus;
+
+	fn status(
+		confirmation_status: TransactionConfirmationStatus,
+		err: Option<TransactionError>,
+	) -> TransactionStatus {
+		TransactionStatus {
+			slot: 100,
+			confirmations: Some(1),
+			err,
+			confirmation_status: Some(confirmation_status),
+		}
+	}
+
+	#[test]
+	fn pending_until_commitment_is_reached() {
+		let processed = status(TransactionConfirmationStatus::Processed, None);
+		let confirmed = status(TransactionConfirmationStatus::Confirmed, None);
+		let commitment = CommitmentConfig::confirmed();
+
+		check!(TransactionConfirmation::from_status(None, commitment, 10, 20).is_none());
+		check!(
+			TransactionConfirmation::from_status(Some(&processed), commitment, 10, 20).is_none()
+		);
+		check!(
+			TransactionConfirmation::from_status(Some(&confirmed), commitment, 10, 20)
+				== Some(TransactionConfirmation::Confirmed)
+		);
+	}
+
+	#[test]
+	fn failed_transaction() {
+		let error = TransactionError::InstructionError(0, InstructionError::Custom(1));
+		let failed = status(
+			TransactionConfirmationStatus::Confirmed,
+			Some(error.clone()),
+		);
+		let confirmation = TransactionConfirmation::from_status(
+			Some(&failed),
+			CommitmentConfig::confirmed(),
+			10,
+			20,
+		);
+
+		check!(confirmation == Some(TransactionConfirmation::Failed(error)));
+	}
+
+	#[test]
+	fn expired_blockhash() {
+		let processed = status(TransactionConfirmationStatus::Processed, None);
+		let commitment = CommitmentConfig::confirmed();
+
+		check!(TransactionConfirmation::from_status(None, commitment, 20, 20).is_none());
+		check!(
+			TransactionConfirmation::from_status(None, commitment, 21, 20)
+				== Some(TransactionConfirmation::Expired)
+		);
+		check!(
+			TransactionConfirmation::from_status(Some(&processed), commitment, 21, 20).is_none()
+		);
+	}
+}
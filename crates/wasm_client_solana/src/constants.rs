@@ -1,3 +1,17 @@
 pub const COMPUTE_UNIT_MAX_LIMIT: usize = 1_400_000;
 pub const COMPUTE_UNIT_DEFAULT_LIMIT: usize = 200_000;
 pub const MAX_LOOKUP_ADDRESSES_PER_TRANSACTION: usize = 30;
+/// The maximum number of accounts accepted by `getRecentPrioritizationFees`.
+pub const MAX_PRIORITIZATION_FEE_ACCOUNTS: usize = 128;
+/// The maximum size of a serialized transaction, which is the data size of a
+/// network packet.
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+/// The maximum number of accounts accepted by `getMultipleAccounts`.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// The maximum number of `getMultipleAccounts` requests which are sent at the
+/// same time when more than [`MAX_MULTIPLE_ACCOUNTS`] accounts are requested.
+pub const MAX_CONCURRENT_MULTIPLE_ACCOUNTS_REQUESTS: usize = 4;
+/// The maximum number of lookup tables for which
+/// [`compile_with_lookup_tables`](crate::compile_with_lookup_tables) tries
+/// every subset. More tables are chosen greedily.
+pub const MAX_EXHAUSTIVE_LOOKUP_TABLES: usize = 10;
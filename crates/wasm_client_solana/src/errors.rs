@@ -1,4 +1,5 @@
 use std::fmt;
+use std::time::Duration;
 
 use serde::Deserialize;
 use serde::Serialize;
@@ -7,6 +8,7 @@ use solana_sdk::signer::SignerError;
 use wallet_standard::IntoWalletError;
 use wallet_standard::WalletError;
 
+use crate::MAX_TRANSACTION_SIZE;
 use crate::nonce_utils::NonceError;
 
 pub const DEFAULT_ERROR_CODE: u16 = 500u16;
@@ -79,11 +81,93 @@ pub enum ClientError {
 	/// The nonce error.
 	#[error("{0}")]
 	Nonce(#[from] NonceError),
+	/// The rpc responded with an unsuccessful HTTP status.
+	#[error("{0}")]
+	Http(#[from] HttpError),
+	/// The request could not be sent or the connection failed before a
+	/// response was received.
+	#[error("Transport: {0}")]
+	Transport(String),
+	/// The request was aborted because no response was received within the
+	/// configured timeout.
+	#[error("Request timed out after {0:?}")]
+	Timeout(Duration),
+	/// The serialized transaction doesn't fit into a network packet.
+	#[error("{0}")]
+	TransactionTooLarge(#[from] TransactionTooLargeError),
 	/// The string of any unsupported errors.
 	#[error("Other: {0}")]
 	Other(String),
 }
 
+impl ClientError {
+	/// Whether the request which caused this error can be retried. This is
+	/// true for rate limits, server errors, transport failures and timeouts.
+	pub fn is_retryable(&self) -> bool {
+		match self {
+			Self::Http(error) => error.is_retryable(),
+			Self::Transport(_) | Self::Timeout(_) => true,
+			_ => false,
+		}
+	}
+
+	/// The delay requested by the rpc with the `Retry-After` header.
+	pub fn retry_after(&self) -> Option<Duration> {
+		match self {
+			Self::Http(error) => error.retry_after,
+			_ => None,
+		}
+	}
+}
+
+/// An unsuccessful HTTP response from the rpc.
+#[derive(Clone, Debug, Serialize, Deserialize, thiserror::Error)]
+#[error("HTTP status {status}: {message}")]
+pub struct HttpError {
+	/// The HTTP status code.
+	pub status: u16,
+	/// The body of the response.
+	pub message: String,
+	/// The delay requested by the `Retry-After` header.
+	pub retry_after: Option<Duration>,
+}
+
+impl HttpError {
+	/// The status code `429 Too Many Requests` which is used by rpc
+	/// providers when rate limiting requests.
+	pub const TOO_MANY_REQUESTS: u16 = 429;
+	/// The status code `401 Unauthorized` which is used by rpc providers when
+	/// the api key or token is missing or invalid.
+	pub const UNAUTHORIZED: u16 = 401;
+
+	/// Whether this is a rate limit or server error.
+	pub fn is_retryable(&self) -> bool {
+		self.status == Self::TOO_MANY_REQUESTS || self.status >= 500
+	}
+}
+
+/// A transaction which is larger than [`MAX_TRANSACTION_SIZE`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error(
+	"Transaction is {size} bytes which is {excess} bytes over the limit of {MAX_TRANSACTION_SIZE} \
+	 bytes"
+)]
+pub struct TransactionTooLargeError {
+	/// The size of the serialized transaction in bytes.
+	pub size: usize,
+	/// The number of bytes the transaction is over the limit.
+	pub excess: usize,
+}
+
+/// Parse the value of a `Retry-After` header.
+///
+/// Only the delay in seconds is supported. The HTTP-date form is ignored so
+/// the retry falls back to the exponential backoff of the
+/// [`RetryPolicy`](crate::RetryPolicy).
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+	value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
 impl IntoWalletError for ClientError {}
 impl IntoWalletError for ClientWebSocketError {}
 impl IntoWalletError for RpcError {}
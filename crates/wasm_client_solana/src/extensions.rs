@@ -1,19 +1,15 @@
 #![allow(clippy::manual_async_fn)]
 
 use std::future::Future;
-use std::ops::Div;
-use std::ops::Mul;
 
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
-use solana_sdk::address_lookup_table::instruction::create_lookup_table;
-use solana_sdk::address_lookup_table::instruction::extend_lookup_table;
-use solana_sdk::compute_budget::ComputeBudgetInstruction;
 use solana_sdk::hash::Hash;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::message::CompileError;
 use solana_sdk::message::VersionedMessage;
 use solana_sdk::message::v0;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::SIGNATURE_BYTES;
 use solana_sdk::signature::Signature;
 use solana_sdk::signer::SignerError;
 use solana_sdk::signers::Signers;
@@ -27,11 +23,11 @@ use wallet_standard::WalletSolanaPubkey;
 use wallet_standard::WalletSolanaSignMessage;
 use wallet_standard::WalletSolanaSignTransaction;
 
-use crate::COMPUTE_UNIT_MAX_LIMIT;
-use crate::ClientError;
 use crate::ClientResult;
-use crate::MAX_LOOKUP_ADDRESSES_PER_TRANSACTION;
+use crate::MAX_TRANSACTION_SIZE;
+use crate::PriorityFeeConfig;
 use crate::SolanaRpcClient;
+use crate::TransactionTooLargeError;
 
 /// Add extensions which make it possible to partially sign a versioned
 /// transaction.
@@ -48,6 +44,18 @@ pub trait VersionedTransactionExtension {
 		address_lookup_tables: &[AddressLookupTableAccount],
 		recent_blockhash: Hash,
 	) -> Result<VersionedTransaction, CompileError>;
+	/// Create a new unsigned transaction in the same way as
+	/// [`VersionedTransactionExtension::new_unsigned_v0`] with a compute unit
+	/// price instruction prepended. The price is estimated from the recent
+	/// prioritization fees of the writable accounts.
+	fn new_unsigned_v0_with_priority_fee(
+		rpc: &SolanaRpcClient,
+		payer: &Pubkey,
+		instructions: &[Instruction],
+		address_lookup_tables: &[AddressLookupTableAccount],
+		recent_blockhash: Hash,
+		config: PriorityFeeConfig,
+	) -> impl Future<Output = ClientResult<VersionedTransaction>>;
 	fn new_unsigned(message: VersionedMessage) -> VersionedTransaction;
 	/// Attempt to sign this transaction with provided signers.
 	fn try_sign<T: Signers + ?Sized>(
@@ -131,6 +139,27 @@ impl VersionedTransactionExtension for VersionedTransaction {
 		Ok(Self::new_unsigned(versioned_message))
 	}
 
+	fn new_unsigned_v0_with_priority_fee(
+		rpc: &SolanaRpcClient,
+		payer: &Pubkey,
+		instructions: &[Instruction],
+		address_lookup_tables: &[AddressLookupTableAccount],
+		recent_blockhash: Hash,
+		config: PriorityFeeConfig,
+	) -> impl Future<Output = ClientResult<Self>> {
+		async move {
+			let instructions = rpc.with_priority_fee(instructions, config).await?;
+			let transaction = Self::new_unsigned_v0(
+				payer,
+				&instructions,
+				address_lookup_tables,
+				recent_blockhash,
+			)?;
+
+			Ok(transaction)
+		}
+	}
+
 	/// Create an unsigned transction from a [`VersionedMessage`].
 	fn new_unsigned(message: VersionedMessage) -> Self {
 		let signatures =
@@ -306,139 +335,43 @@ impl VersionedTransactionExtension for VersionedTransaction {
 
 pub trait VersionedMessageExtension {
 	fn into_versioned_transaction(self) -> VersionedTransaction;
+	/// The size in bytes of the serialized transaction for this message,
+	/// including the signatures.
+	fn transaction_size(&self) -> usize;
+	/// Check that the transaction for this message fits within
+	/// [`MAX_TRANSACTION_SIZE`] and return its size.
+	fn check_transaction_size(&self) -> Result<usize, TransactionTooLargeError> {
+		let size = self.transaction_size();
+
+		if size > MAX_TRANSACTION_SIZE {
+			Err(TransactionTooLargeError {
+				size,
+				excess: size - MAX_TRANSACTION_SIZE,
+			})
+		} else {
+			Ok(size)
+		}
+	}
 }
 
 impl VersionedMessageExtension for VersionedMessage {
 	fn into_versioned_transaction(self) -> VersionedTransaction {
 		VersionedTransaction::new_unsigned(self)
 	}
-}
-
-/// Initialize a lookup table that can be used with versioned transactions.
-pub async fn initialize_address_lookup_table<
-	P: WalletSolanaSignTransaction + WalletSolanaPubkey,
-	A: WalletSolanaSignMessage + WalletSolanaPubkey,
->(
-	rpc: &SolanaRpcClient,
-	payer_wallet: &P,
-	authority_signer: &A,
-	addresses: &[Pubkey],
-) -> ClientResult<Pubkey> {
-	let payer = payer_wallet.try_solana_pubkey()?;
-	let authority = authority_signer.try_solana_pubkey()?;
-
-	if addresses.len() > 256 {
-		return Err(ClientError::Other(
-			"Too many addresses passed to to the VersionedTransaction".into(),
-		));
-	}
-
-	let mut address_chunks = addresses.chunks(MAX_LOOKUP_ADDRESSES_PER_TRANSACTION);
-	let chunk = address_chunks.next().unwrap_or(&[]);
-	let slot = rpc.get_slot().await? - 1;
-	let (lookup_table_instruction, lookup_table_address) =
-		create_lookup_table(payer, authority, slot);
-	let mut instructions = vec![lookup_table_instruction];
 
-	if !chunk.is_empty() {
-		let instruction =
-			extend_lookup_table(lookup_table_address, authority, Some(payer), chunk.into());
+	fn transaction_size(&self) -> usize {
+		let signatures = usize::from(self.header().num_required_signatures);
+		let message_size = self.serialize().len();
 
-		instructions.push(instruction);
+		short_vec_length_size(signatures) + signatures * SIGNATURE_BYTES + message_size
 	}
-
-	let mut versioned_transaction = VersionedTransaction::new_unsigned_v0(
-		&payer,
-		&instructions,
-		&[],
-		rpc.get_latest_blockhash().await?,
-	)?
-	.sign_with_wallet(payer_wallet, None)
-	.await?;
-
-	if payer != authority && !chunk.is_empty() {
-		versioned_transaction
-			.sign_async(authority_signer, None)
-			.await?;
-	}
-
-	rpc.send_transaction(&versioned_transaction).await?;
-	rpc.wait_for_new_block(1).await?;
-
-	let instructions = address_chunks
-		.map(|chunk| {
-			extend_lookup_table(lookup_table_address, authority, Some(payer), chunk.into())
-		})
-		.collect::<Vec<_>>();
-
-	let Some(instruction) = instructions.first().map(wasm_safe_instruction_clone) else {
-		return Ok(lookup_table_address);
-	};
-
-	let compute_limit_instruction =
-		ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_MAX_LIMIT as u32);
-	let versioned_transaction = VersionedTransaction::new_unsigned_v0(
-		&payer,
-		&[compute_limit_instruction, instruction],
-		&[],
-		rpc.get_latest_blockhash().await?,
-	)?;
-	let Some(compute_units) = rpc
-		.simulate_transaction(&versioned_transaction)
-		.await?
-		.value
-		.units_consumed
-		.map(|c| c.mul(110).div(100))
-	else {
-		return Err(ClientError::Other(
-			"Could not calculate the optimal compute units".into(),
-		));
-	};
-	let chunk_size = COMPUTE_UNIT_MAX_LIMIT.div(compute_units as usize);
-	let instruction_chunks = instructions.chunks(chunk_size);
-
-	for instruction_chunk in instruction_chunks {
-		let compute_limit_instruction = ComputeBudgetInstruction::set_compute_unit_limit(
-			instruction_chunk.len().mul(compute_units as usize) as u32,
-		);
-		let mut instructions = vec![compute_limit_instruction];
-		let mut cloned_chunk = instruction_chunk
-			.iter()
-			.map(wasm_safe_instruction_clone)
-			.collect::<Vec<_>>();
-		instructions.append(&mut cloned_chunk);
-		let mut versioned_transaction = VersionedTransaction::new_unsigned_v0(
-			&payer,
-			&instructions,
-			&[],
-			rpc.get_latest_blockhash().await?,
-		)?
-		.sign_with_wallet(payer_wallet, None)
-		.await?;
-
-		if payer != authority {
-			versioned_transaction
-				.sign_async(authority_signer, None)
-				.await?;
-		}
-
-		rpc.send_transaction(&versioned_transaction).await?;
-	}
-
-	Ok(lookup_table_address)
 }
 
-fn wasm_safe_instruction_clone(instruction: &Instruction) -> Instruction {
-	#[cfg(target_arch = "wasm32")]
-	{
-		Instruction {
-			program_id: instruction.program_id.clone(),
-			accounts: instruction.accounts.clone(),
-			data: instruction.data.clone(),
-		}
-	}
-	#[cfg(not(target_arch = "wasm32"))]
-	{
-		instruction.clone()
+/// The number of bytes used to encode the length of a `short_vec`.
+fn short_vec_length_size(length: usize) -> usize {
+	match length {
+		0..0x80 => 1,
+		0x80..0x4000 => 2,
+		_ => 3,
 	}
 }
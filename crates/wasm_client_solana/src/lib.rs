@@ -1,30 +1,51 @@
 #![doc = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/readme.md"))]
 
+pub use crate::account_store::*;
+pub use crate::address_lookup_table::*;
+pub use crate::batch::*;
 pub use crate::client::*;
+pub use crate::compute_units::*;
+pub use crate::confirmation::*;
 pub use crate::constants::*;
 pub use crate::errors::*;
 pub use crate::extensions::*;
 pub use crate::methods::*;
+pub use crate::priority_fee::*;
 pub use crate::providers::*;
 pub use crate::rpc_config::*;
+pub use crate::simulation::*;
 pub use crate::solana_client::*;
+pub use crate::transaction_planner::*;
+pub use crate::transaction_sender::*;
 pub use crate::utils::spawn_local;
 
+mod account_store;
+mod address_lookup_table;
+mod batch;
 mod client;
+mod compute_units;
+mod confirmation;
 mod constants;
 mod errors;
 mod extensions;
 mod methods;
+mod metrics;
 pub mod nonce_utils;
+mod priority_fee;
 mod providers;
 pub mod rpc_config;
 pub mod rpc_filter;
 pub mod rpc_response;
 pub mod runtime;
+mod simulation;
 pub mod solana_account_decoder;
 mod solana_client;
 pub mod solana_rpc_client_api;
 pub mod solana_transaction_status;
+#[cfg(test)]
+mod test_utils;
+mod transaction_planner;
+mod transaction_sender;
 pub mod utils;
 
 pub mod prelude {
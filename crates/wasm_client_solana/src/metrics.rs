@@ -0,0 +1,271 @@
+//! Structured events for requests and subscriptions which are emitted when
+//! the `metrics` feature is enabled. Events are emitted with `tracing` on
+//! native targets and logged to the browser console on `wasm`.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::utils::now;
+
+/// The method recorded for JSON-RPC batch requests.
+pub(crate) const BATCH_METHOD: &str = "batch";
+
+/// A completed http request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RequestEvent<'a> {
+	method: &'a str,
+	latency_ms: f64,
+	/// The size of the request body in bytes.
+	request_size: usize,
+	/// The size of the response body in bytes.
+	response_size: Option<usize>,
+	status: Option<u16>,
+	/// The code of the rpc error returned in the response body.
+	rpc_error_code: Option<i64>,
+	error: Option<String>,
+}
+
+/// A change to the active subscriptions of a websocket connection.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionEvent<'a> {
+	method: &'a str,
+	subscribed: bool,
+	/// The number of active subscriptions after the change.
+	active_subscriptions: usize,
+}
+
+/// A notification received by a subscription.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationEvent<'a> {
+	method: &'a str,
+	subscription: u64,
+	/// The number of notifications received by the subscription.
+	notifications: u64,
+	/// The average number of notifications per second since the
+	/// subscription was created.
+	rate: f64,
+}
+
+/// Records the metrics of a single http request.
+pub(crate) struct RequestMetrics {
+	method: &'static str,
+	started_at: Duration,
+	request_size: usize,
+	/// The status and size of the response.
+	response: Mutex<Option<(u16, usize)>>,
+}
+
+impl RequestMetrics {
+	pub(crate) fn start(method: &'static str, request_size: usize) -> Self {
+		Self {
+			method,
+			started_at: now(),
+			request_size,
+			response: Mutex::default(),
+		}
+	}
+
+	/// Record the status and size of the response once its body was read.
+	pub(crate) fn response(&self, status: u16, size: usize) {
+		*self.response.lock().unwrap_or_else(PoisonError::into_inner) = Some((status, size));
+	}
+
+	/// Emit the event for the request which completed with the `result`.
+	pub(crate) fn finish(self, result: &ClientResult<Value>) {
+		let (rpc_error_code, error) = match result {
+			Ok(value) => (value.pointer("/error/code").and_then(Value::as_i64), None),
+			Err(ClientError::Rpc(error)) => {
+				(Some(i64::from(error.error.code)), Some(error.to_string()))
+			}
+			Err(error) => (None, Some(error.to_string())),
+		};
+		let response = self
+			.response
+			.into_inner()
+			.unwrap_or_else(PoisonError::into_inner);
+		let event = RequestEvent {
+			method: self.method,
+			latency_ms: now().saturating_sub(self.started_at).as_secs_f64() * 1000.0,
+			request_size: self.request_size,
+			response_size: response.map(|(_, size)| size),
+			status: response.map(|(status, _)| status),
+			rpc_error_code,
+			error,
+		};
+
+		#[cfg(test)]
+		tests::record_request(&event);
+
+		emit_request(&event);
+	}
+}
+
+/// Emit the event for a subscription which was created or removed.
+pub(crate) fn record_subscription(method: &str, subscribed: bool, active_subscriptions: usize) {
+	emit_subscription(&SubscriptionEvent {
+		method,
+		subscribed,
+		active_subscriptions,
+	});
+}
+
+/// Emit the event for a notification which was received by the
+/// `subscription` which was created at `subscribed_at`.
+pub(crate) fn record_notification(
+	method: &str,
+	subscription: u64,
+	notifications: u64,
+	subscribed_at: Duration,
+) {
+	let elapsed = now().saturating_sub(subscribed_at).as_secs_f64();
+	let rate = if elapsed > 0.0 {
+		notifications as f64 / elapsed
+	} else {
+		notifications as f64
+	};
+
+	emit_notification(&NotificationEvent {
+		method,
+		subscription,
+		notifications,
+		rate,
+	});
+}
+
+/// Run the `future` within a span for the rpc `method`.
+pub(crate) fn instrument<F: Future>(
+	method: &'static str,
+	future: F,
+) -> impl Future<Output = F::Output> {
+	cfg_if::cfg_if! {
+		if #[cfg(all(feature = "metrics", any(feature = "ssr", not(target_arch = "wasm32"))))] {
+			tracing::Instrument::instrument(future, tracing::debug_span!("rpc_request", method))
+		} else {
+			let _ = method;
+			future
+		}
+	}
+}
+
+cfg_if::cfg_if! {
+	if #[cfg(all(feature = "metrics", any(feature = "ssr", not(target_arch = "wasm32"))))] {
+		fn emit_request(event: &RequestEvent<'_>) {
+			tracing::debug!(
+				target: "wasm_client_solana::http",
+				method = event.method,
+				latency_ms = event.latency_ms,
+				request_size = event.request_size,
+				response_size = event.response_size,
+				status = event.status,
+				rpc_error_code = event.rpc_error_code,
+				error = event.error.as_deref(),
+				"rpc request",
+			);
+		}
+
+		fn emit_subscription(event: &SubscriptionEvent<'_>) {
+			tracing::debug!(
+				target: "wasm_client_solana::websocket",
+				method = event.method,
+				subscribed = event.subscribed,
+				active_subscriptions = event.active_subscriptions,
+				"rpc subscription",
+			);
+		}
+
+		fn emit_notification(event: &NotificationEvent<'_>) {
+			tracing::trace!(
+				target: "wasm_client_solana::websocket",
+				method = event.method,
+				subscription = event.subscription,
+				notifications = event.notifications,
+				rate = event.rate,
+				"rpc notification",
+			);
+		}
+	} else if #[cfg(feature = "metrics")] {
+		fn emit_request(event: &RequestEvent<'_>) {
+			log_to_console("rpc request", event);
+		}
+
+		fn emit_subscription(event: &SubscriptionEvent<'_>) {
+			log_to_console("rpc subscription", event);
+		}
+
+		fn emit_notification(event: &NotificationEvent<'_>) {
+			log_to_console("rpc notification", event);
+		}
+
+		fn log_to_console(message: &str, event: &impl Serialize) {
+			if let Ok(value) = serde_wasm_bindgen::to_value(event) {
+				web_sys::console::debug_2(&wasm_bindgen::JsValue::from_str(message), &value);
+			}
+		}
+	} else {
+		fn emit_request(_event: &RequestEvent<'_>) {}
+
+		fn emit_subscription(_event: &SubscriptionEvent<'_>) {}
+
+		fn emit_notification(_event: &NotificationEvent<'_>) {}
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use std::cell::RefCell;
+
+	use assert2::check;
+	use serde_json::json;
+
+	use super::*;
+
+	thread_local! {
+		static REQUESTS: RefCell<Vec<Value>> = const { RefCell::new(vec![]) };
+	}
+
+	pub(super) fn record_request(event: &RequestEvent<'_>) {
+		REQUESTS.with_borrow_mut(|requests| requests.push(serde_json::to_value(event).unwrap()));
+	}
+
+	/// Take the request events which were emitted on the current thread.
+	#[cfg(feature = "ssr")]
+	pub(crate) fn take_requests() -> Vec<Value> {
+		REQUESTS.take()
+	}
+
+	#[test]
+	fn serializes_request_events() {
+		let event = RequestEvent {
+			method: "getSlot",
+			latency_ms: 12.5,
+			request_size: 48,
+			response_size: Some(40),
+			status: Some(200),
+			rpc_error_code: Some(-32601),
+			error: None,
+		};
+
+		check!(
+			serde_json::to_value(&event).unwrap()
+				== json!({
+					"method": "getSlot",
+					"latencyMs": 12.5,
+					"requestSize": 48,
+					"responseSize": 40,
+					"status": 200,
+					"rpcErrorCode": -32601,
+					"error": null,
+				})
+		);
+	}
+}
@@ -5,16 +5,21 @@ use serde::Serialize;
 use solana_sdk::account::Account;
 use solana_sdk::account::ReadableAccount;
 use solana_sdk::account_utils::StateMut;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::nonce::State;
 use solana_sdk::nonce::state::Data;
 use solana_sdk::nonce::state::Versions;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction;
 use solana_sdk::system_program;
+use solana_sdk::transaction::VersionedTransaction;
 
 use crate::ClientResult;
 use crate::SolanaRpcClient;
+use crate::VersionedTransactionExtension;
 use crate::rpc_config::RpcAccountInfoConfig;
 
 #[derive(Clone, Debug, Serialize, Deserialize, thiserror::Error, PartialEq, Eq)]
@@ -31,6 +36,8 @@ pub enum NonceError {
 	InvalidAuthority { provided: Pubkey, expected: Pubkey },
 	#[error("invalid state for requested operation")]
 	InvalidStateForOperation,
+	#[error("transaction does not start by advancing the nonce account ({0})")]
+	MissingAdvanceNonceInstruction(Pubkey),
 	#[error("client error: {0}")]
 	Client(String),
 }
@@ -137,6 +144,147 @@ pub fn data_from_state(state: &State) -> Result<&Data, NonceError> {
 	}
 }
 
+/// The instructions which create and initialize the durable transaction nonce
+/// account `nonce_pubkey` funded with `lamports` by the `payer`.
+///
+/// Both the `payer` and the `nonce_pubkey` must sign the transaction.
+pub fn create_instructions(
+	payer: &Pubkey,
+	nonce_pubkey: &Pubkey,
+	authority: &Pubkey,
+	lamports: u64,
+) -> Vec<Instruction> {
+	system_instruction::create_nonce_account(payer, nonce_pubkey, authority, lamports)
+}
+
+/// The instruction which advances the nonce stored in the account. It must be
+/// the first instruction of a durable nonce transaction.
+pub fn advance_instruction(nonce_pubkey: &Pubkey, data: &Data) -> Instruction {
+	system_instruction::advance_nonce_account(nonce_pubkey, &data.authority)
+}
+
+/// The instruction which changes the authority of the nonce account.
+///
+/// # Errors
+///
+/// Returns [`NonceError::InvalidAuthority`] if `authority` is not the current
+/// authority of the nonce.
+pub fn authorize_instruction(
+	nonce_pubkey: &Pubkey,
+	data: &Data,
+	authority: &Pubkey,
+	new_authority: &Pubkey,
+) -> Result<Instruction, NonceError> {
+	check_authority(data, authority)?;
+
+	Ok(system_instruction::authorize_nonce_account(
+		nonce_pubkey,
+		authority,
+		new_authority,
+	))
+}
+
+/// The instruction which withdraws `lamports` from the nonce account to the
+/// recipient `to`. Withdrawing the full balance closes the nonce account.
+///
+/// # Errors
+///
+/// Returns [`NonceError::InvalidAuthority`] if `authority` is not the current
+/// authority of the nonce.
+pub fn withdraw_instruction(
+	nonce_pubkey: &Pubkey,
+	data: &Data,
+	authority: &Pubkey,
+	to: &Pubkey,
+	lamports: u64,
+) -> Result<Instruction, NonceError> {
+	check_authority(data, authority)?;
+
+	Ok(system_instruction::withdraw_nonce_account(
+		nonce_pubkey,
+		authority,
+		to,
+		lamports,
+	))
+}
+
+/// Check that `authority` is the authority of the nonce.
+///
+/// # Errors
+///
+/// Returns [`NonceError::InvalidAuthority`] if the authorities don't match.
+pub fn check_authority(data: &Data, authority: &Pubkey) -> Result<(), NonceError> {
+	if &data.authority == authority {
+		Ok(())
+	} else {
+		Err(NonceError::InvalidAuthority {
+			provided: *authority,
+			expected: data.authority,
+		})
+	}
+}
+
+/// Create an unsigned durable nonce transaction. The first instruction advances
+/// the nonce account and the stored nonce is used as the blockhash, so the
+/// transaction can be signed offline and doesn't expire until the nonce is
+/// advanced.
+///
+/// The nonce authority must sign the transaction.
+pub fn new_unsigned_transaction(
+	payer: &Pubkey,
+	nonce_pubkey: &Pubkey,
+	data: &Data,
+	instructions: &[Instruction],
+	address_lookup_tables: &[AddressLookupTableAccount],
+) -> ClientResult<VersionedTransaction> {
+	let instructions = [&[advance_instruction(nonce_pubkey, data)], instructions].concat();
+	let transaction = VersionedTransaction::new_unsigned_v0(
+		payer,
+		&instructions,
+		address_lookup_tables,
+		data.blockhash(),
+	)?;
+
+	Ok(transaction)
+}
+
+/// Check that the durable nonce `transaction` can still be processed with the
+/// current nonce `data`.
+///
+/// # Errors
+///
+/// Returns [`NonceError::MissingAdvanceNonceInstruction`] if the first
+/// instruction doesn't advance the nonce account. Returns
+/// [`NonceError::InvalidHash`] if the nonce was advanced since the transaction
+/// was created.
+pub fn check_transaction(
+	transaction: &VersionedTransaction,
+	nonce_pubkey: &Pubkey,
+	data: &Data,
+) -> Result<(), NonceError> {
+	let message = &transaction.message;
+	let advances_nonce = transaction.uses_durable_nonce()
+		&& message
+			.instructions()
+			.first()
+			.and_then(|instruction| instruction.accounts.first())
+			.and_then(|index| message.static_account_keys().get(usize::from(*index)))
+			== Some(nonce_pubkey);
+
+	if !advances_nonce {
+		return Err(NonceError::MissingAdvanceNonceInstruction(*nonce_pubkey));
+	}
+
+	let provided = *message.recent_blockhash();
+	let expected = data.blockhash();
+
+	if provided == expected {
+		Ok(())
+	} else {
+		Err(NonceError::InvalidHash { provided, expected })
+	}
+}
+
 impl SolanaRpcClient {
 	/// Deserialize the state of a durable transaction nonce account.
 	///
@@ -165,4 +313,125 @@ impl SolanaRpcClient {
 
 		Ok(data)
 	}
+
+	/// The instructions which create a rent exempt durable transaction nonce
+	/// account.
+	///
+	/// Both the `payer` and the `nonce_pubkey` must sign the transaction.
+	pub async fn create_nonce_account_instructions(
+		&self,
+		payer: &Pubkey,
+		nonce_pubkey: &Pubkey,
+		authority: &Pubkey,
+	) -> ClientResult<Vec<Instruction>> {
+		let lamports = self
+			.get_minimum_balance_for_rent_exemption(State::size())
+			.await?;
+
+		Ok(create_instructions(
+			payer,
+			nonce_pubkey,
+			authority,
+			lamports,
+		))
+	}
+
+	/// Create an unsigned durable nonce transaction with the nonce currently
+	/// stored in the `nonce_pubkey` account. See [`new_unsigned_transaction`].
+	pub async fn new_unsigned_nonce_transaction(
+		&self,
+		payer: &Pubkey,
+		nonce_pubkey: &Pubkey,
+		instructions: &[Instruction],
+		address_lookup_tables: &[AddressLookupTableAccount],
+	) -> ClientResult<VersionedTransaction> {
+		let data = self.get_nonce_account_data(nonce_pubkey).await?;
+
+		new_unsigned_transaction(
+			payer,
+			nonce_pubkey,
+			&data,
+			instructions,
+			address_lookup_tables,
+		)
+	}
+
+	/// Check that the durable nonce `transaction` can still be processed with
+	/// the nonce currently stored in the `nonce_pubkey` account. See
+	/// [`check_transaction`].
+	pub async fn check_nonce_transaction(
+		&self,
+		transaction: &VersionedTransaction,
+		nonce_pubkey: &Pubkey,
+	) -> ClientResult<()> {
+		let data = self.get_nonce_account_data(nonce_pubkey).await?;
+		check_transaction(transaction, nonce_pubkey, &data)?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use solana_sdk::nonce::state::DurableNonce;
+
+	use super::*;
+
+	fn nonce_data(authority: Pubkey) -> Data {
+		Data::new(
+			authority,
+			DurableNonce::from_blockhash(&Hash::new_unique()),
+			5000,
+		)
+	}
+
+	#[test]
+	fn nonce_transaction() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let nonce_pubkey = Pubkey::new_unique();
+		let data = nonce_data(payer);
+		let instruction = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+		let transaction =
+			new_unsigned_transaction(&payer, &nonce_pubkey, &data, &[instruction], &[])?;
+
+		check!(transaction.uses_durable_nonce());
+		check!(*transaction.message.recent_blockhash() == data.blockhash());
+		check!(check_transaction(&transaction, &nonce_pubkey, &data) == Ok(()));
+
+		let advanced = nonce_data(payer);
+		check!(
+			check_transaction(&transaction, &nonce_pubkey, &advanced)
+				== Err(NonceError::InvalidHash {
+					provided: data.blockhash(),
+					expected: advanced.blockhash(),
+				})
+		);
+
+		let other_nonce = Pubkey::new_unique();
+		check!(
+			check_transaction(&transaction, &other_nonce, &data)
+				== Err(NonceError::MissingAdvanceNonceInstruction(other_nonce))
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn authority_is_checked() {
+		let nonce_pubkey = Pubkey::new_unique();
+		let authority = Pubkey::new_unique();
+		let other = Pubkey::new_unique();
+		let data = nonce_data(authority);
+
+		check!(authorize_instruction(&nonce_pubkey, &data, &authority, &other).is_ok());
+		check!(withdraw_instruction(&nonce_pubkey, &data, &authority, &other, 1).is_ok());
+		check!(
+			authorize_instruction(&nonce_pubkey, &data, &other, &other)
+				== Err(NonceError::InvalidAuthority {
+					provided: other,
+					expected: authority,
+				})
+		);
+	}
 }
@@ -0,0 +1,205 @@
+use solana_sdk::compute_budget;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use typed_builder::TypedBuilder;
+
+use crate::ClientResult;
+use crate::MAX_PRIORITIZATION_FEE_ACCOUNTS;
+use crate::SolanaRpcClient;
+use crate::rpc_response::RpcPrioritizationFee;
+
+/// Configure how the priority fee is estimated from the recent
+/// prioritization fees of the writable accounts of a transaction.
+///
+/// ```
+/// use wasm_client_solana::PriorityFeeConfig;
+///
+/// let config = PriorityFeeConfig::builder()
+/// 	.percentile(75)
+/// 	.max_micro_lamports(1_000_000)
+/// 	.build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypedBuilder)]
+pub struct PriorityFeeConfig {
+	/// The percentile of the recent prioritization fees to use, between `0`
+	/// and `100`.
+	#[builder(default = 50)]
+	pub percentile: u8,
+	/// The lowest compute unit price in micro-lamports.
+	#[builder(default)]
+	pub min_micro_lamports: u64,
+	/// The highest compute unit price in micro-lamports.
+	#[builder(default, setter(strip_option))]
+	pub max_micro_lamports: Option<u64>,
+}
+
+impl Default for PriorityFeeConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl PriorityFeeConfig {
+	/// Pick the compute unit price from the recent prioritization `fees`.
+	pub fn compute_unit_price(&self, fees: &[RpcPrioritizationFee]) -> u64 {
+		let price = fee_percentile(fees, self.percentile).max(self.min_micro_lamports);
+
+		match self.max_micro_lamports {
+			Some(max) => price.min(max),
+			None => price,
+		}
+	}
+}
+
+/// The `percentile` of the prioritization `fees` using the nearest rank
+/// method. Returns `0` when there are no fees.
+pub fn fee_percentile(fees: &[RpcPrioritizationFee], percentile: u8) -> u64 {
+	let mut fees = fees
+		.iter()
+		.map(|fee| fee.prioritization_fee)
+		.collect::<Vec<_>>();
+
+	if fees.is_empty() {
+		return 0;
+	}
+
+	fees.sort_unstable();
+
+	let percentile = usize::from(percentile.min(100));
+	let rank = (percentile * fees.len()).div_ceil(100);
+
+	fees[rank.saturating_sub(1)]
+}
+
+/// The unique writable accounts of the `instructions` in the order they
+/// first appear.
+pub fn writable_accounts(instructions: &[Instruction]) -> Vec<Pubkey> {
+	let mut accounts: Vec<Pubkey> = vec![];
+
+	for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+		if meta.is_writable && !accounts.contains(&meta.pubkey) {
+			accounts.push(meta.pubkey);
+		}
+	}
+
+	accounts
+}
+
+/// Whether the `instructions` already set a compute unit price.
+pub fn has_compute_unit_price(instructions: &[Instruction]) -> bool {
+	let discriminator = ComputeBudgetInstruction::set_compute_unit_price(0).data[0];
+
+	instructions.iter().any(|instruction| {
+		instruction.program_id == compute_budget::id()
+			&& instruction.data.first() == Some(&discriminator)
+	})
+}
+
+impl SolanaRpcClient {
+	/// Estimate the compute unit price in micro-lamports for a transaction
+	/// with the provided `instructions` from the recent prioritization fees
+	/// of their writable accounts.
+	pub async fn estimate_priority_fee(
+		&self,
+		instructions: &[Instruction],
+		config: PriorityFeeConfig,
+	) -> ClientResult<u64> {
+		let mut accounts = writable_accounts(instructions);
+		accounts.truncate(MAX_PRIORITIZATION_FEE_ACCOUNTS);
+
+		let fees = self
+			.get_recent_prioritization_fees_with_accounts(accounts)
+			.await?;
+
+		Ok(config.compute_unit_price(&fees))
+	}
+
+	/// Prepend a [`ComputeBudgetInstruction::set_compute_unit_price`]
+	/// instruction with the estimated priority fee to the `instructions`. The
+	/// `instructions` are returned unchanged when they already set a compute
+	/// unit price.
+	pub async fn with_priority_fee(
+		&self,
+		instructions: &[Instruction],
+		config: PriorityFeeConfig,
+	) -> ClientResult<Vec<Instruction>> {
+		if has_compute_unit_price(instructions) {
+			return Ok(instructions.to_vec());
+		}
+
+		let price = self.estimate_priority_fee(instructions, config).await?;
+		let mut result = Vec::with_capacity(instructions.len() + 1);
+		result.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+		result.extend_from_slice(instructions);
+
+		Ok(result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use solana_sdk::instruction::AccountMeta;
+
+	use super::*;
+
+	fn fees(values: &[u64]) -> Vec<RpcPrioritizationFee> {
+		values
+			.iter()
+			.map(|fee| {
+				RpcPrioritizationFee {
+					slot: 0,
+					prioritization_fee: *fee,
+				}
+			})
+			.collect()
+	}
+
+	#[test]
+	fn percentile() {
+		let fees = fees(&[50, 10, 40, 20, 30]);
+
+		check!(fee_percentile(&fees, 0) == 10);
+		check!(fee_percentile(&fees, 50) == 30);
+		check!(fee_percentile(&fees, 75) == 40);
+		check!(fee_percentile(&fees, 100) == 50);
+		check!(fee_percentile(&[], 50) == 0);
+	}
+
+	#[test]
+	fn compute_unit_price_is_clamped() {
+		let fees = fees(&[0, 100, 10_000]);
+		let config = PriorityFeeConfig::builder()
+			.percentile(100)
+			.min_micro_lamports(500)
+			.max_micro_lamports(1_000)
+			.build();
+
+		check!(config.compute_unit_price(&fees) == 1_000);
+		check!(config.compute_unit_price(&[]) == 500);
+	}
+
+	#[test]
+	fn writable_accounts_are_unique() {
+		let program_id = Pubkey::new_unique();
+		let writable = Pubkey::new_unique();
+		let readonly = Pubkey::new_unique();
+		let instruction = Instruction::new_with_bytes(
+			program_id,
+			&[],
+			vec![
+				AccountMeta::new(writable, true),
+				AccountMeta::new_readonly(readonly, false),
+			],
+		);
+
+		let accounts = writable_accounts(&[instruction.clone(), instruction.clone()]);
+		check!(accounts == [writable]);
+		check!(!has_compute_unit_price(&[instruction.clone()]));
+		check!(has_compute_unit_price(&[
+			ComputeBudgetInstruction::set_compute_unit_price(1),
+			instruction
+		]));
+	}
+}
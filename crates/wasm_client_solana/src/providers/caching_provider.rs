@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use futures::future::Shared;
+use serde_json::Value;
+use solana_sdk::commitment_config::CommitmentLevel;
+use typed_builder::TypedBuilder;
+
+use crate::ClientResult;
+use crate::RpcProvider;
+use crate::utils::now;
+
+type SharedProvider = Arc<dyn RpcProvider + Send + Sync + 'static>;
+type SharedRequest = Shared<BoxFuture<'static, ClientResult<Value>>>;
+
+/// The methods which are cached by default and the duration their responses
+/// are reused.
+pub const DEFAULT_CACHE_TTLS: &[(&str, Duration)] = &[
+	("getAccountInfo", Duration::from_secs(2)),
+	("getMultipleAccounts", Duration::from_secs(2)),
+	("getBalance", Duration::from_secs(2)),
+	("getTokenAccountBalance", Duration::from_secs(2)),
+	("getTokenAccountsByOwner", Duration::from_secs(2)),
+	("getTokenSupply", Duration::from_secs(2)),
+	("getMinimumBalanceForRentExemption", Duration::from_secs(60)),
+	("getGenesisHash", Duration::from_secs(3600)),
+];
+
+/// The methods which change state. Sending one of them through the provider
+/// invalidates the cached responses which were read below `finalized`
+/// commitment.
+const WRITE_METHODS: &[&str] = &["sendTransaction", "requestAirdrop"];
+
+enum CacheEntry {
+	/// The request is in flight and is shared by every caller.
+	Pending(SharedRequest),
+	Ready {
+		value: Value,
+		expires_at: Duration,
+		commitment: CommitmentLevel,
+	},
+}
+
+/// An [`RpcProvider`] which caches responses of read methods so that repeated
+/// requests for the same data don't reach the network.
+///
+/// Responses are keyed by the method and its params and are reused for the
+/// duration configured for the method. Concurrent requests with the same key
+/// share a single in-flight request. Methods without a configured duration are
+/// sent directly, as are batches.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use solana_sdk::commitment_config::CommitmentConfig;
+/// use wasm_client_solana::CachingProvider;
+/// use wasm_client_solana::DEVNET;
+/// use wasm_client_solana::HttpProvider;
+/// use wasm_client_solana::SolanaRpcClient;
+///
+/// let provider = CachingProvider::builder()
+/// 	.provider(HttpProvider::new(DEVNET))
+/// 	.ttls([("getAccountInfo", Duration::from_millis(500))])
+/// 	.build();
+/// let rpc = SolanaRpcClient::new_with_provider(Arc::new(provider), CommitmentConfig::confirmed());
+/// ```
+#[derive(derive_more::Debug, TypedBuilder)]
+pub struct CachingProvider {
+	/// The provider which sends the requests which aren't cached.
+	#[builder(setter(transform = |provider: impl RpcProvider + Send + Sync + 'static| {
+		Arc::new(provider) as SharedProvider
+	}))]
+	#[debug(skip)]
+	provider: SharedProvider,
+	/// The duration responses are cached for each method. Defaults to
+	/// [`DEFAULT_CACHE_TTLS`].
+	#[builder(
+		default = DEFAULT_CACHE_TTLS.iter().map(|(method, ttl)| ((*method).to_string(), *ttl)).collect(),
+		setter(transform = |ttls: impl IntoIterator<Item = (impl Into<String>, Duration)>| {
+			ttls.into_iter().map(|(method, ttl)| (method.into(), ttl)).collect()
+		})
+	)]
+	ttls: HashMap<String, Duration>,
+	#[builder(default, setter(skip))]
+	#[debug(skip)]
+	entries: Mutex<HashMap<String, CacheEntry>>,
+}
+
+impl CachingProvider {
+	/// Remove every cached response. Requests which are in flight are
+	/// completed but their responses aren't cached.
+	pub fn invalidate(&self) {
+		self.entries().clear();
+	}
+
+	/// Remove the cached responses of the `method`.
+	pub fn invalidate_method(&self, method: &str) {
+		let prefix = format!("{method}:");
+
+		self.entries().retain(|key, _| !key.starts_with(&prefix));
+	}
+
+	/// Remove the cached responses which were read with the `commitment` or a
+	/// lower commitment, e.g. `confirmed` removes `processed` and `confirmed`
+	/// responses.
+	pub fn invalidate_commitment(&self, commitment: CommitmentLevel) {
+		self.entries().retain(|_, entry| {
+			match entry {
+				CacheEntry::Ready {
+					commitment: entry_commitment,
+					..
+				} => commitment_rank(*entry_commitment) > commitment_rank(commitment),
+				CacheEntry::Pending(_) => false,
+			}
+		});
+	}
+
+	fn entries(&self) -> MutexGuard<'_, HashMap<String, CacheEntry>> {
+		self.entries
+			.lock()
+			.unwrap_or_else(std::sync::PoisonError::into_inner)
+	}
+
+	/// Get the cached response or the shared in-flight request for the `key`,
+	/// starting the request when neither exists.
+	fn request(
+		&self,
+		key: &str,
+		method: &'static str,
+		request: Value,
+	) -> Result<Value, SharedRequest> {
+		let mut entries = self.entries();
+
+		match entries.get(key) {
+			Some(CacheEntry::Ready {
+				value, expires_at, ..
+			}) if *expires_at > now() => return Ok(value.clone()),
+			Some(CacheEntry::Pending(shared)) => return Err(shared.clone()),
+			_ => {}
+		}
+
+		let provider = self.provider.clone();
+		let shared = async move { provider.send(method, request).await }
+			.boxed()
+			.shared();
+		entries.insert(key.to_string(), CacheEntry::Pending(shared.clone()));
+
+		Err(shared)
+	}
+
+	/// Store the `result` of the `shared` request unless the entry was
+	/// replaced or invalidated while the request was in flight.
+	fn complete(
+		&self,
+		key: &str,
+		shared: &SharedRequest,
+		result: &ClientResult<Value>,
+		ttl: Duration,
+		commitment: CommitmentLevel,
+	) {
+		let mut entries = self.entries();
+
+		if !matches!(entries.get(key), Some(CacheEntry::Pending(pending)) if pending.ptr_eq(shared))
+		{
+			return;
+		}
+
+		match result {
+			// rpc errors are returned in the response body and aren't cached
+			Ok(value) if value.get("error").is_none() => {
+				entries.insert(
+					key.to_string(),
+					CacheEntry::Ready {
+						value: value.clone(),
+						expires_at: now() + ttl,
+						commitment,
+					},
+				);
+			}
+			_ => {
+				entries.remove(key);
+			}
+		}
+	}
+}
+
+#[async_trait]
+impl RpcProvider for CachingProvider {
+	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
+		let Some(ttl) = self.ttls.get(method).copied() else {
+			let result = self.provider.send(method, request).await;
+
+			if WRITE_METHODS.contains(&method) {
+				self.invalidate_commitment(CommitmentLevel::Confirmed);
+			}
+
+			return result;
+		};
+
+		let key = format!("{method}:{request}");
+		let commitment = request_commitment(&request);
+		let shared = match self.request(&key, method, request) {
+			Ok(value) => return Ok(value),
+			Err(shared) => shared,
+		};
+		let result = shared.clone().await;
+		self.complete(&key, &shared, &result, ttl, commitment);
+
+		result
+	}
+
+	async fn send_batch(&self, requests: Vec<(&'static str, Value)>) -> ClientResult<Vec<Value>> {
+		self.provider.send_batch(requests).await
+	}
+
+	fn url(&self) -> String {
+		self.provider.url()
+	}
+}
+
+/// The commitment of the `request` which is read from the config object in
+/// its params. The rpc defaults to `finalized` when no commitment is provided.
+fn request_commitment(request: &Value) -> CommitmentLevel {
+	let config = match request {
+		Value::Array(params) => params.last(),
+		Value::Object(_) => Some(request),
+		_ => None,
+	};
+
+	config
+		.and_then(|config| config.get("commitment"))
+		.and_then(|commitment| serde_json::from_value(commitment.clone()).ok())
+		.unwrap_or(CommitmentLevel::Finalized)
+}
+
+fn commitment_rank(commitment: CommitmentLevel) -> u8 {
+	match commitment {
+		CommitmentLevel::Processed => 0,
+		CommitmentLevel::Confirmed => 1,
+		CommitmentLevel::Finalized => 2,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::AtomicU32;
+	use std::sync::atomic::Ordering;
+
+	use assert2::check;
+	use serde_json::json;
+
+	use super::*;
+	use crate::test_utils::TestProvider;
+
+	/// Responds with the number of requests received before the request.
+	fn counting_provider() -> Arc<TestProvider> {
+		let calls = AtomicU32::new(0);
+		let provider =
+			TestProvider::new(move |_, _| Ok(json!(calls.fetch_add(1, Ordering::SeqCst))))
+				.with_delay(Duration::from_millis(10));
+
+		Arc::new(provider)
+	}
+
+	fn caching_provider(mock: &Arc<TestProvider>, ttl: Duration) -> CachingProvider {
+		CachingProvider::builder()
+			.provider(mock.clone())
+			.ttls([("getAccountInfo", ttl)])
+			.build()
+	}
+
+	fn account_request(commitment: &str) -> Value {
+		json!(["11111111111111111111111111111111", { "commitment": commitment }])
+	}
+
+	#[test]
+	fn deduplicates_in_flight_requests() -> anyhow::Result<()> {
+		let mock = counting_provider();
+		let provider = caching_provider(&mock, Duration::ZERO);
+		let request = account_request("confirmed");
+		let (first, second) = futures::executor::block_on(futures::future::join(
+			provider.send("getAccountInfo", request.clone()),
+			provider.send("getAccountInfo", request.clone()),
+		));
+
+		check!(first? == second?);
+		check!(mock.calls() == 1);
+
+		// the response expired immediately
+		futures::executor::block_on(provider.send("getAccountInfo", request))?;
+		check!(mock.calls() == 2);
+
+		Ok(())
+	}
+
+	#[test]
+	fn caches_by_method_and_params() -> anyhow::Result<()> {
+		let mock = counting_provider();
+		let provider = caching_provider(&mock, Duration::from_secs(60));
+
+		futures::executor::block_on(async {
+			provider
+				.send("getAccountInfo", account_request("confirmed"))
+				.await?;
+			provider
+				.send("getAccountInfo", account_request("confirmed"))
+				.await?;
+			check!(mock.calls() == 1);
+
+			provider
+				.send("getAccountInfo", account_request("finalized"))
+				.await?;
+			provider.send("getSlot", Value::Null).await?;
+			provider.send("getSlot", Value::Null).await?;
+			check!(mock.calls() == 4);
+
+			anyhow::Ok(())
+		})
+	}
+
+	#[test]
+	fn invalidates_unfinalized_responses_after_writes() -> anyhow::Result<()> {
+		let mock = counting_provider();
+		let provider = caching_provider(&mock, Duration::from_secs(60));
+
+		futures::executor::block_on(async {
+			provider
+				.send("getAccountInfo", account_request("confirmed"))
+				.await?;
+			provider
+				.send("getAccountInfo", account_request("finalized"))
+				.await?;
+			provider.send("sendTransaction", json!(["AQ=="])).await?;
+			check!(mock.calls() == 3);
+
+			provider
+				.send("getAccountInfo", account_request("finalized"))
+				.await?;
+			check!(mock.calls() == 3);
+
+			provider
+				.send("getAccountInfo", account_request("confirmed"))
+				.await?;
+			check!(mock.calls() == 4);
+
+			provider.invalidate_method("getAccountInfo");
+			provider
+				.send("getAccountInfo", account_request("finalized"))
+				.await?;
+			check!(mock.calls() == 5);
+
+			anyhow::Ok(())
+		})
+	}
+}
@@ -0,0 +1,476 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+
+use crate::ClientError;
+use crate::ClientResponse;
+use crate::ClientResult;
+use crate::GetHealthRequest;
+use crate::GetHealthResponse;
+use crate::RpcProvider;
+use crate::methods::HttpMethod;
+use crate::solana_client::parse_response;
+use crate::utils::now;
+
+type SharedProvider = Arc<dyn RpcProvider + Send + Sync + 'static>;
+
+/// The JSON-RPC error codes of endpoints which can't serve requests right
+/// now. `-32005` is returned by nodes which are behind while `-32429` and
+/// `429` are used by rpc providers when rate limiting requests.
+const RETRYABLE_RPC_ERROR_CODES: [i64; 3] = [-32005, -32429, 429];
+
+/// The strategy used by the [`FailoverProvider`] to choose which endpoint
+/// receives a request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoutingStrategy {
+	/// Send every request to the first healthy endpoint in the order they
+	/// were provided.
+	#[default]
+	PrimaryWithFailover,
+	/// Rotate through the healthy endpoints.
+	RoundRobin,
+	/// Send every request to the healthy endpoint with the lowest average
+	/// latency.
+	LowestLatency,
+}
+
+/// The health of an endpoint of the [`FailoverProvider`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointStatus {
+	/// The url of the endpoint.
+	pub url: String,
+	/// Whether the endpoint is currently used for requests.
+	pub healthy: bool,
+	/// The number of requests which failed since the last successful
+	/// request.
+	pub consecutive_failures: u32,
+	/// The average latency of successful requests.
+	pub latency: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct EndpointState {
+	consecutive_failures: u32,
+	/// The time the endpoint was marked as unhealthy.
+	unhealthy_since: Option<Duration>,
+	latency: Option<Duration>,
+}
+
+/// An [`RpcProvider`] which routes requests to multiple endpoints, e.g. a
+/// paid rpc with a public fallback.
+///
+/// Requests which fail because of transport failures, timeouts, rate limits
+/// or server errors are retried with the next endpoint. This includes rpc
+/// errors in the response body of endpoints which are behind or rate
+/// limited. Endpoints are marked
+/// as unhealthy after repeated failures and are only used again once they
+/// pass a `getHealth` check.
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use solana_sdk::commitment_config::CommitmentConfig;
+/// use wasm_client_solana::DEVNET;
+/// use wasm_client_solana::FailoverProvider;
+/// use wasm_client_solana::HttpProvider;
+/// use wasm_client_solana::RoutingStrategy;
+/// use wasm_client_solana::SolanaRpcClient;
+///
+/// let provider = FailoverProvider::builder()
+/// 	.endpoints([
+/// 		HttpProvider::new("https://paid.rpc.example.com"),
+/// 		HttpProvider::new(DEVNET),
+/// 	])
+/// 	.strategy(RoutingStrategy::PrimaryWithFailover)
+/// 	.build();
+/// let rpc = SolanaRpcClient::new_with_provider(Arc::new(provider), CommitmentConfig::confirmed());
+/// ```
+#[derive(derive_more::Debug, TypedBuilder)]
+pub struct FailoverProvider {
+	/// The providers for each endpoint in order of preference.
+	#[builder(setter(transform = |endpoints: impl IntoIterator<Item = impl RpcProvider + Send + Sync + 'static>| {
+		endpoints.into_iter().map(|provider| Arc::new(provider) as SharedProvider).collect()
+	}))]
+	#[debug(skip)]
+	endpoints: Vec<SharedProvider>,
+	#[builder(default = endpoints.iter().map(|_| Mutex::default()).collect(), setter(skip))]
+	states: Vec<Mutex<EndpointState>>,
+	/// The strategy used to choose the endpoint for each request.
+	#[builder(default)]
+	strategy: RoutingStrategy,
+	/// The number of consecutive failures after which an endpoint is marked
+	/// as unhealthy.
+	#[builder(default = 3)]
+	failure_threshold: u32,
+	/// The duration to wait before an unhealthy endpoint is checked again
+	/// with `getHealth`.
+	#[builder(default = Duration::from_secs(30))]
+	health_check_interval: Duration,
+	#[builder(default, setter(skip))]
+	next: AtomicUsize,
+}
+
+impl FailoverProvider {
+	/// The current health of every endpoint.
+	pub fn endpoints(&self) -> Vec<EndpointStatus> {
+		self.endpoints
+			.iter()
+			.enumerate()
+			.map(|(index, provider)| {
+				let state = self.state(index);
+
+				EndpointStatus {
+					url: provider.url(),
+					healthy: state.unhealthy_since.is_none(),
+					consecutive_failures: state.consecutive_failures,
+					latency: state.latency,
+				}
+			})
+			.collect()
+	}
+
+	/// Check the health of every endpoint with `getHealth`. Healthy endpoints
+	/// are used for requests again.
+	pub async fn check_health(&self) {
+		let checks = (0..self.endpoints.len()).map(|index| self.check_endpoint(index));
+
+		futures::future::join_all(checks).await;
+	}
+
+	async fn check_endpoint(&self, index: usize) -> bool {
+		let healthy = match self.endpoints[index]
+			.send(GetHealthRequest::NAME, Value::Null)
+			.await
+			.and_then(parse_response::<ClientResponse<GetHealthResponse>>)
+		{
+			Ok(response) => response.result.0 == "ok",
+			Err(_) => false,
+		};
+
+		self.update_state(index, |state| {
+			if healthy {
+				state.consecutive_failures = 0;
+				state.unhealthy_since = None;
+			} else {
+				state.unhealthy_since = Some(now());
+			}
+		});
+
+		healthy
+	}
+
+	fn state(&self, index: usize) -> EndpointState {
+		*self.states[index]
+			.lock()
+			.unwrap_or_else(std::sync::PoisonError::into_inner)
+	}
+
+	fn update_state(&self, index: usize, update: impl FnOnce(&mut EndpointState)) {
+		let mut state = self.states[index]
+			.lock()
+			.unwrap_or_else(std::sync::PoisonError::into_inner);
+
+		update(&mut state);
+	}
+
+	fn record_success(&self, index: usize, latency: Duration) {
+		self.update_state(index, |state| {
+			state.consecutive_failures = 0;
+			state.unhealthy_since = None;
+			state.latency = Some(state.latency.map_or(latency, |average| {
+				average.mul_f64(0.8) + latency.mul_f64(0.2)
+			}));
+		});
+	}
+
+	fn record_failure(&self, index: usize) {
+		let threshold = self.failure_threshold;
+
+		self.update_state(index, |state| {
+			state.consecutive_failures += 1;
+
+			if state.consecutive_failures >= threshold && state.unhealthy_since.is_none() {
+				state.unhealthy_since = Some(now());
+			}
+		});
+	}
+
+	/// The endpoints ordered by the routing strategy.
+	fn order(&self) -> Vec<usize> {
+		let count = self.endpoints.len();
+
+		match self.strategy {
+			RoutingStrategy::PrimaryWithFailover => (0..count).collect(),
+			RoutingStrategy::RoundRobin if count > 0 => {
+				let start = self.next.fetch_add(1, Ordering::Relaxed) % count;
+				(0..count).map(|offset| (start + offset) % count).collect()
+			}
+			RoutingStrategy::RoundRobin => vec![],
+			RoutingStrategy::LowestLatency => {
+				let mut order: Vec<usize> = (0..count).collect();
+				order.sort_by_key(|index| self.state(*index).latency.unwrap_or_default());
+				order
+			}
+		}
+	}
+
+	/// The endpoints to try in order. Unhealthy endpoints which are due for a
+	/// health check are checked first and unhealthy endpoints are only used
+	/// as a last resort.
+	async fn candidates(&self) -> Vec<usize> {
+		let mut healthy = vec![];
+		let mut unhealthy = vec![];
+
+		for index in self.order() {
+			match self.state(index).unhealthy_since {
+				None => healthy.push(index),
+				Some(since) if now().saturating_sub(since) >= self.health_check_interval => {
+					if self.check_endpoint(index).await {
+						healthy.push(index);
+					} else {
+						unhealthy.push(index);
+					}
+				}
+				Some(_) => unhealthy.push(index),
+			}
+		}
+
+		healthy.extend(unhealthy);
+		healthy
+	}
+
+	/// Send the `request` to each candidate until it succeeds. Responses for
+	/// which `rpc_error_code` returns one of the
+	/// [`RETRYABLE_RPC_ERROR_CODES`] count as failures and are only returned
+	/// when every endpoint failed.
+	async fn route<T, F, Fut>(
+		&self,
+		mut request: F,
+		rpc_error_code: impl Fn(&T) -> Option<i64>,
+	) -> ClientResult<T>
+	where
+		F: FnMut(SharedProvider) -> Fut,
+		Fut: Future<Output = ClientResult<T>>,
+	{
+		let mut last_result = None;
+
+		for index in self.candidates().await {
+			let provider = self.endpoints[index].clone();
+			let start = now();
+
+			match request(provider).await {
+				Ok(value) => {
+					match rpc_error_code(&value) {
+						Some(code) if RETRYABLE_RPC_ERROR_CODES.contains(&code) => {
+							log::warn!(
+								"rpc endpoint {} responded with error code {code}",
+								self.endpoints[index].url()
+							);
+							self.record_failure(index);
+							last_result = Some(Ok(value));
+						}
+						_ => {
+							self.record_success(index, now().saturating_sub(start));
+							return Ok(value);
+						}
+					}
+				}
+				Err(error) if error.is_retryable() => {
+					log::warn!(
+						"rpc endpoint {} failed: {error}",
+						self.endpoints[index].url()
+					);
+					self.record_failure(index);
+					last_result = Some(Err(error));
+				}
+				Err(error) => return Err(error),
+			}
+		}
+
+		last_result.unwrap_or_else(|| Err(ClientError::Other("no rpc endpoints available".into())))
+	}
+}
+
+#[async_trait]
+impl RpcProvider for FailoverProvider {
+	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
+		self.route(
+			|provider| {
+				let request = request.clone();
+				async move { provider.send(method, request).await }
+			},
+			rpc_error_code,
+		)
+		.await
+	}
+
+	async fn send_batch(&self, requests: Vec<(&'static str, Value)>) -> ClientResult<Vec<Value>> {
+		self.route(
+			|provider| {
+				let requests = requests.clone();
+				async move { provider.send_batch(requests).await }
+			},
+			|responses: &Vec<Value>| responses.iter().find_map(rpc_error_code),
+		)
+		.await
+	}
+
+	/// The url of the first endpoint which is also used for websocket
+	/// subscriptions by
+	/// [`SolanaRpcClient::new_with_provider`](crate::SolanaRpcClient::new_with_provider).
+	fn url(&self) -> String {
+		self.endpoints
+			.first()
+			.map(RpcProvider::url)
+			.unwrap_or_default()
+	}
+}
+
+/// The code of the rpc error in the `error` field of the `response`.
+fn rpc_error_code(response: &Value) -> Option<i64> {
+	response.pointer("/error/code").and_then(Value::as_i64)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::AtomicBool;
+
+	use assert2::check;
+	use serde_json::json;
+
+	use super::*;
+	use crate::test_utils::TestProvider;
+	use crate::test_utils::rpc_error;
+
+	/// An endpoint which responds with its `url` while it is `healthy`.
+	fn endpoint(url: &'static str, healthy: bool) -> (Arc<TestProvider>, Arc<AtomicBool>) {
+		let healthy = Arc::new(AtomicBool::new(healthy));
+		let is_healthy = healthy.clone();
+		let provider = TestProvider::new(move |method, _| {
+			if !is_healthy.load(Ordering::SeqCst) {
+				return Err(ClientError::Transport("connection refused".into()));
+			}
+
+			if method == GetHealthRequest::NAME {
+				return Ok(json!("ok"));
+			}
+
+			Ok(json!(url))
+		});
+
+		(Arc::new(provider.with_url(url)), healthy)
+	}
+
+	fn send(provider: &FailoverProvider) -> ClientResult<Value> {
+		futures::executor::block_on(provider.send("getSlot", Value::Null))
+			.map(|response| response["result"].clone())
+	}
+
+	#[test]
+	fn fails_over_to_next_endpoint() -> anyhow::Result<()> {
+		let (primary, _) = endpoint("primary", false);
+		let (fallback, _) = endpoint("fallback", true);
+		let provider = FailoverProvider::builder()
+			.endpoints([primary, fallback.clone()])
+			.failure_threshold(2)
+			.build();
+
+		for _ in 0..3 {
+			check!(send(&provider)? == "fallback");
+		}
+
+		let endpoints = provider.endpoints();
+		check!(!endpoints[0].healthy);
+		check!(endpoints[0].consecutive_failures == 2);
+		check!(endpoints[1].healthy);
+		check!(fallback.requests("getSlot").len() == 3);
+
+		Ok(())
+	}
+
+	#[test]
+	fn round_robin() -> anyhow::Result<()> {
+		let provider = FailoverProvider::builder()
+			.endpoints([endpoint("a", true).0, endpoint("b", true).0])
+			.strategy(RoutingStrategy::RoundRobin)
+			.build();
+
+		let urls = (0..4)
+			.map(|_| send(&provider))
+			.collect::<ClientResult<Vec<_>>>()?;
+		check!(urls == ["a", "b", "a", "b"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn unhealthy_endpoint_is_rechecked() -> anyhow::Result<()> {
+		let (primary, primary_healthy) = endpoint("primary", false);
+		let provider = FailoverProvider::builder()
+			.endpoints([primary, endpoint("fallback", true).0])
+			.failure_threshold(1)
+			.health_check_interval(Duration::ZERO)
+			.build();
+
+		check!(send(&provider)? == "fallback");
+		check!(!provider.endpoints()[0].healthy);
+
+		primary_healthy.store(true, Ordering::SeqCst);
+		check!(send(&provider)? == "primary");
+		check!(provider.endpoints()[0].healthy);
+
+		Ok(())
+	}
+
+	#[test]
+	fn fails_over_on_retryable_rpc_errors() -> anyhow::Result<()> {
+		let behind = Arc::new(TestProvider::new(|_, _| {
+			Err(rpc_error(-32005, "Node is behind"))
+		}));
+		let (fallback, _) = endpoint("fallback", true);
+		let provider = FailoverProvider::builder()
+			.endpoints([behind.clone(), fallback])
+			.failure_threshold(1)
+			.build();
+
+		check!(send(&provider)? == "fallback");
+		check!(behind.calls() == 1);
+		check!(!provider.endpoints()[0].healthy);
+
+		Ok(())
+	}
+
+	#[test]
+	fn returns_other_rpc_errors() -> anyhow::Result<()> {
+		let invalid = Arc::new(TestProvider::new(|_, _| {
+			Err(rpc_error(-32602, "Invalid params"))
+		}));
+		let (fallback, _) = endpoint("fallback", true);
+		let provider = FailoverProvider::builder()
+			.endpoints([invalid, fallback.clone()])
+			.build();
+		let response = futures::executor::block_on(provider.send("getSlot", Value::Null))?;
+
+		check!(rpc_error_code(&response) == Some(-32602));
+		check!(fallback.calls() == 0);
+		check!(provider.endpoints()[0].healthy);
+
+		Ok(())
+	}
+
+	#[test]
+	fn all_endpoints_failing() {
+		let provider = FailoverProvider::builder()
+			.endpoints([endpoint("a", false).0, endpoint("b", false).0])
+			.build();
+
+		check!(let Err(ClientError::Transport(_)) = send(&provider));
+	}
+}
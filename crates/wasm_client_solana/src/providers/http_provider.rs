@@ -1,38 +1,262 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
+use futures::FutureExt;
+use futures_timer::Delay;
+use serde::Serialize;
 use serde_json::Value;
 #[cfg(feature = "ssr")]
 pub use ssr_http_provider::HttpProvider;
 #[cfg(not(feature = "ssr"))]
 pub use wasm_http_provider::HttpProvider;
 
+use crate::ClientError;
 use crate::ClientRequest;
 use crate::ClientResult;
 use crate::DEFAULT_ERROR_CODE;
+use crate::HttpError;
+use crate::RetryPolicy;
 use crate::RpcError;
 use crate::RpcErrorDetails;
+use crate::errors::parse_retry_after;
+use crate::metrics::BATCH_METHOD;
+use crate::metrics::RequestMetrics;
+use crate::retry_with_policy;
+
+/// The default duration to wait for a response before a request is aborted.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[async_trait]
 pub trait RpcProvider {
 	/// Send the request.
 	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value>;
+	/// Send multiple requests and return the responses in the same order as
+	/// the provided `requests`.
+	///
+	/// The default implementation sends every request individually. Providers
+	/// which support JSON-RPC batches should send them as a single request.
+	async fn send_batch(&self, requests: Vec<(&'static str, Value)>) -> ClientResult<Vec<Value>> {
+		let futures = requests
+			.into_iter()
+			.map(|(method, request)| self.send(method, request));
+
+		futures::future::try_join_all(futures).await
+	}
 	/// Get the URL represented by this sender.
 	fn url(&self) -> String;
 }
 
+#[async_trait]
+impl<P: RpcProvider + Send + Sync + ?Sized> RpcProvider for Arc<P> {
+	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
+		(**self).send(method, request).await
+	}
+
+	async fn send_batch(&self, requests: Vec<(&'static str, Value)>) -> ClientResult<Vec<Value>> {
+		(**self).send_batch(requests).await
+	}
+
+	fn url(&self) -> String {
+		(**self).url()
+	}
+}
+
+/// Provides the bearer token which is sent in the `Authorization` header of
+/// every request made by the [`HttpProvider`].
+///
+/// ```
+/// use async_trait::async_trait;
+/// use wasm_client_solana::AuthTokenProvider;
+/// use wasm_client_solana::ClientResult;
+///
+/// struct StaticToken(String);
+///
+/// #[async_trait]
+/// impl AuthTokenProvider for StaticToken {
+/// 	async fn token(&self) -> ClientResult<String> {
+/// 		Ok(self.0.clone())
+/// 	}
+/// }
+/// ```
+#[async_trait]
+pub trait AuthTokenProvider: Send + Sync {
+	/// The current token. Implementations should cache the token and only
+	/// fetch a new one when it has expired.
+	async fn token(&self) -> ClientResult<String>;
+	/// Fetch a new token after the rpc rejected the current one with `401
+	/// Unauthorized`. The request is retried once with the returned token.
+	async fn refresh_token(&self) -> ClientResult<String> {
+		self.token().await
+	}
+}
+
+/// Run the `request` with the token from the `token_provider` and retry it
+/// once with a refreshed token when the rpc responds with `401 Unauthorized`.
+pub(crate) async fn with_auth_token<T, F, Fut>(
+	token_provider: Option<&Arc<dyn AuthTokenProvider>>,
+	mut request: F,
+) -> ClientResult<T>
+where
+	F: FnMut(Option<String>) -> Fut,
+	Fut: Future<Output = ClientResult<T>>,
+{
+	let Some(token_provider) = token_provider else {
+		return request(None).await;
+	};
+
+	let token = token_provider.token().await?;
+
+	match request(Some(token)).await {
+		Err(ClientError::Http(error)) if error.status == HttpError::UNAUTHORIZED => {
+			let token = token_provider.refresh_token().await?;
+			request(Some(token)).await
+		}
+		result => result,
+	}
+}
+
+/// Run the `request` and fail with [`ClientError::Timeout`] when it takes
+/// longer than the `timeout`. The `request` is dropped on timeout, which
+/// aborts the underlying HTTP call.
+pub(crate) async fn with_timeout<T, F>(timeout: Option<Duration>, request: F) -> ClientResult<T>
+where
+	F: Future<Output = ClientResult<T>>,
+{
+	let Some(timeout) = timeout else {
+		return request.await;
+	};
+
+	futures::select! {
+		result = request.fuse() => result,
+		() = Delay::new(timeout).fuse() => Err(ClientError::Timeout(timeout)),
+	}
+}
+
+fn serialize_body(body: &impl Serialize) -> ClientResult<String> {
+	serde_json::to_string(body).map_err(|error| ClientError::Other(error.to_string()))
+}
+
+fn parse_body(body: &str) -> ClientResult<Value> {
+	serde_json::from_str(body).map_err(|error| ClientError::Other(error.to_string()))
+}
+
+fn collect_headers(
+	headers: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+) -> Vec<(String, String)> {
+	headers
+		.into_iter()
+		.map(|(name, value)| (name.into(), value.into()))
+		.collect()
+}
+
+/// Create the body of a JSON-RPC batch request. The index of each request is
+/// used as its `id`.
+pub(crate) fn create_batch_request(requests: Vec<(&'static str, Value)>) -> Vec<ClientRequest> {
+	requests
+		.into_iter()
+		.zip(0u32..)
+		.map(|((method, params), id)| {
+			ClientRequest::builder()
+				.method(method)
+				.id(id)
+				.params(params)
+				.build()
+		})
+		.collect()
+}
+
+/// Order the responses of a JSON-RPC batch request by the `id` of each
+/// request.
+pub(crate) fn demultiplex_batch_response(
+	count: usize,
+	response: Value,
+) -> ClientResult<Vec<Value>> {
+	let Value::Array(responses) = response else {
+		// the whole batch was rejected
+		return match serde_json::from_value::<RpcError>(response.clone()) {
+			Ok(error) => Err(error.into()),
+			Err(_) => {
+				Err(ClientError::Other(format!(
+					"invalid batch response: {response}"
+				)))
+			}
+		};
+	};
+
+	let mut ordered = vec![None; count];
+
+	for response in responses {
+		let index = response
+			.get("id")
+			.and_then(Value::as_u64)
+			.and_then(|id| usize::try_from(id).ok());
+
+		if let Some(slot) = index.and_then(|index| ordered.get_mut(index)) {
+			*slot = Some(response);
+		}
+	}
+
+	ordered
+		.into_iter()
+		.enumerate()
+		.map(|(id, response)| {
+			response.ok_or_else(|| {
+				ClientError::Other(format!("missing response for batch request with id: {id}"))
+			})
+		})
+		.collect()
+}
+
 #[cfg(feature = "ssr")]
 mod ssr_http_provider {
 	use reqwest::Client;
 	use reqwest::header::CONTENT_TYPE;
-	use reqwest::header::HeaderMap;
+	use reqwest::header::RETRY_AFTER;
+	use typed_builder::TypedBuilder;
 
 	use super::*;
-	use crate::ClientError;
 
-	#[derive(Debug, Clone)]
+	/// An [`RpcProvider`] which uses `reqwest` to send requests.
+	///
+	/// ```
+	/// use std::time::Duration;
+	///
+	/// use wasm_client_solana::DEVNET;
+	/// use wasm_client_solana::HttpProvider;
+	/// use wasm_client_solana::RetryPolicy;
+	///
+	/// let provider = HttpProvider::builder()
+	/// 	.url(DEVNET)
+	/// 	.retry_policy(RetryPolicy::default())
+	/// 	.timeout(Duration::from_secs(10))
+	/// 	.headers([("x-api-key", "<API_KEY>")])
+	/// 	.build();
+	/// ```
+	#[derive(derive_more::Debug, Clone, TypedBuilder)]
 	pub struct HttpProvider {
+		#[builder(default, setter(skip))]
 		client: Client,
-		headers: HeaderMap,
+		/// The url of the rpc.
+		#[builder(setter(into))]
 		url: String,
+		/// Retry requests which fail because of rate limits, server errors or
+		/// transport failures.
+		#[builder(default, setter(strip_option))]
+		retry_policy: Option<RetryPolicy>,
+		/// The duration to wait for a response before the request is aborted.
+		/// Set to `None` to wait indefinitely.
+		#[builder(default = Some(DEFAULT_TIMEOUT), setter(into))]
+		timeout: Option<Duration>,
+		/// Static headers which are sent with every request, e.g. the api key
+		/// of a paid rpc provider.
+		#[builder(default, setter(transform = |headers: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>| collect_headers(headers)))]
+		headers: Vec<(String, String)>,
+		/// Provides the bearer token which is sent with every request.
+		#[builder(default, setter(transform = |token_provider: impl AuthTokenProvider + 'static| Some(Arc::new(token_provider) as Arc<dyn AuthTokenProvider>)))]
+		#[debug(skip)]
+		token_provider: Option<Arc<dyn AuthTokenProvider>>,
 	}
 
 	#[async_trait]
@@ -47,48 +271,114 @@ mod ssr_http_provider {
 				.id(1)
 				.params(request)
 				.build();
-			#[cfg(not(target_arch = "wasm32"))]
-			let result: Value = self
-				.client
-				.post(&self.url)
-				.headers(self.headers.clone())
-				.json(&client_request)
-				.send()
-				.await?
-				.json()
-				.await?;
 
-			#[cfg(target_arch = "wasm32")]
-			let result: Value = {
-				let request = self
-					.client
-					.post(&self.url)
-					.headers(self.headers.clone())
-					.json(&client_request)
-					.send();
-				let wrapped_request = send_wrapper::SendWrapper::new(request);
-				let response = wrapped_request.await?.json();
-				let wrapped_response = send_wrapper::SendWrapper::new(response);
-				let result = wrapped_response.await?;
-				result
-			};
+			retry_with_policy(self.retry_policy.as_ref(), || {
+				self.post(method, &client_request)
+			})
+			.await
+		}
+
+		async fn send_batch(
+			&self,
+			requests: Vec<(&'static str, Value)>,
+		) -> ClientResult<Vec<Value>> {
+			if requests.is_empty() {
+				return Ok(vec![]);
+			}
+
+			let count = requests.len();
+			let batch_request = create_batch_request(requests);
+			let response = retry_with_policy(self.retry_policy.as_ref(), || {
+				self.post(BATCH_METHOD, &batch_request)
+			})
+			.await?;
 
-			Ok(result)
+			demultiplex_batch_response(count, response)
 		}
 	}
 
 	impl HttpProvider {
-		pub fn new(url: impl Into<String>) -> Self {
-			let client = Client::new();
-			let url = url.into();
-			let mut headers = HeaderMap::new();
-			headers.append(CONTENT_TYPE, "application/json".parse().unwrap());
+		async fn post<T: Serialize + Sync>(
+			&self,
+			method: &'static str,
+			body: &T,
+		) -> ClientResult<Value> {
+			let body = serialize_body(body)?;
+			// started outside of the timeout so requests which time out are
+			// recorded as well
+			let metrics = RequestMetrics::start(method, body.len());
+			let request = with_auth_token(self.token_provider.as_ref(), |token| {
+				self.send_body(body.clone(), token, &metrics)
+			});
+			let result = with_timeout(self.timeout, request).await;
+			metrics.finish(&result);
+
+			result
+		}
 
-			Self {
-				client,
-				headers,
-				url,
+		async fn send_body(
+			&self,
+			body: String,
+			token: Option<String>,
+			metrics: &RequestMetrics,
+		) -> ClientResult<Value> {
+			let mut request = self
+				.client
+				.post(&self.url)
+				.header(CONTENT_TYPE, "application/json");
+
+			for (name, value) in &self.headers {
+				request = request.header(name, value);
+			}
+
+			if let Some(token) = token {
+				request = request.bearer_auth(token);
 			}
+
+			let request = request.body(body).send();
+			#[cfg(target_arch = "wasm32")]
+			let request = send_wrapper::SendWrapper::new(request);
+			let response = request.await.map_err(|error| {
+				if error.is_builder() {
+					// invalid headers can't be fixed by retrying the request
+					ClientError::Other(error.to_string())
+				} else {
+					ClientError::Transport(error.to_string())
+				}
+			})?;
+			let status = response.status();
+
+			if !status.is_success() {
+				let retry_after = response
+					.headers()
+					.get(RETRY_AFTER)
+					.and_then(|value| value.to_str().ok())
+					.and_then(parse_retry_after);
+				let text = response.text();
+				#[cfg(target_arch = "wasm32")]
+				let text = send_wrapper::SendWrapper::new(text);
+				let message = text.await.unwrap_or_default();
+				metrics.response(status.as_u16(), message.len());
+
+				return Err(HttpError {
+					status: status.as_u16(),
+					message,
+					retry_after,
+				}
+				.into());
+			}
+
+			let text = response.text();
+			#[cfg(target_arch = "wasm32")]
+			let text = send_wrapper::SendWrapper::new(text);
+			let text = text.await?;
+			metrics.response(status.as_u16(), text.len());
+
+			parse_body(&text)
+		}
+
+		pub fn new(url: impl Into<String>) -> Self {
+			Self::builder().url(url).build()
 		}
 	}
 
@@ -123,11 +413,11 @@ mod wasm_http_provider {
 	use pin_project::pin_project;
 	use pin_project::pinned_drop;
 	use send_wrapper::SendWrapper;
+	use typed_builder::TypedBuilder;
 	use wasm_bindgen::prelude::*;
 	use web_sys::AbortController;
 
 	use super::*;
-	use crate::ClientError;
 
 	#[pin_project(PinnedDrop)]
 	struct AbortableRequest<F: Future<Output = Result<gloo_net::http::Response, gloo_net::Error>>> {
@@ -178,13 +468,50 @@ mod wasm_http_provider {
 		}
 	}
 
-	#[derive(Debug, Clone)]
-	pub struct HttpProvider(String);
+	/// An [`RpcProvider`] which uses `fetch` to send requests. Requests are
+	/// aborted when the returned future is dropped.
+	///
+	/// ```
+	/// use std::time::Duration;
+	///
+	/// use wasm_client_solana::DEVNET;
+	/// use wasm_client_solana::HttpProvider;
+	/// use wasm_client_solana::RetryPolicy;
+	///
+	/// let provider = HttpProvider::builder()
+	/// 	.url(DEVNET)
+	/// 	.retry_policy(RetryPolicy::default())
+	/// 	.timeout(Duration::from_secs(10))
+	/// 	.headers([("x-api-key", "<API_KEY>")])
+	/// 	.build();
+	/// ```
+	#[derive(derive_more::Debug, Clone, TypedBuilder)]
+	pub struct HttpProvider {
+		/// The url of the rpc.
+		#[builder(setter(into))]
+		url: String,
+		/// Retry requests which fail because of rate limits, server errors or
+		/// transport failures.
+		#[builder(default, setter(strip_option))]
+		retry_policy: Option<RetryPolicy>,
+		/// The duration to wait for a response before the request is aborted.
+		/// Set to `None` to wait indefinitely.
+		#[builder(default = Some(DEFAULT_TIMEOUT), setter(into))]
+		timeout: Option<Duration>,
+		/// Static headers which are sent with every request, e.g. the api key
+		/// of a paid rpc provider.
+		#[builder(default, setter(transform = |headers: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>| collect_headers(headers)))]
+		headers: Vec<(String, String)>,
+		/// Provides the bearer token which is sent with every request.
+		#[builder(default, setter(transform = |token_provider: impl AuthTokenProvider + 'static| Some(Arc::new(token_provider) as Arc<dyn AuthTokenProvider>)))]
+		#[debug(skip)]
+		token_provider: Option<Arc<dyn AuthTokenProvider>>,
+	}
 
 	#[async_trait]
 	impl RpcProvider for HttpProvider {
 		fn url(&self) -> String {
-			self.0.clone()
+			self.url.clone()
 		}
 
 		async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
@@ -194,27 +521,97 @@ mod wasm_http_provider {
 				.params(request)
 				.build();
 
-			let future = async move {
-				let controller = AbortController::new().unwrap_throw();
-				let signal = controller.signal();
-				let request = gloo_net::http::Request::post(&self.0)
-					.abort_signal(Some(&signal))
-					.json(&client_request)?;
-				let response = AbortableRequest::new(request.send(), controller).await?;
-				let value = response.json().await?;
+			let future = retry_with_policy(self.retry_policy.as_ref(), || {
+				self.post(method, &client_request)
+			});
 
-				Ok::<Value, ClientError>(value)
-			};
+			SendWrapper::new(future).await
+		}
+
+		async fn send_batch(
+			&self,
+			requests: Vec<(&'static str, Value)>,
+		) -> ClientResult<Vec<Value>> {
+			if requests.is_empty() {
+				return Ok(vec![]);
+			}
 
-			let value = SendWrapper::new(future).await?;
+			let count = requests.len();
+			let batch_request = create_batch_request(requests);
+			let future = retry_with_policy(self.retry_policy.as_ref(), || {
+				self.post(BATCH_METHOD, &batch_request)
+			});
+			let response = SendWrapper::new(future).await?;
 
-			Ok(value)
+			demultiplex_batch_response(count, response)
 		}
 	}
 
 	impl HttpProvider {
+		async fn post<T: Serialize>(&self, method: &'static str, body: &T) -> ClientResult<Value> {
+			let body = serialize_body(body)?;
+			// started outside of the timeout so requests which time out are
+			// recorded as well
+			let metrics = RequestMetrics::start(method, body.len());
+			let request = with_auth_token(self.token_provider.as_ref(), |token| {
+				self.send_body(body.clone(), token, &metrics)
+			});
+			let result = with_timeout(self.timeout, request).await;
+			metrics.finish(&result);
+
+			result
+		}
+
+		async fn send_body(
+			&self,
+			body: String,
+			token: Option<String>,
+			metrics: &RequestMetrics,
+		) -> ClientResult<Value> {
+			let controller = AbortController::new().unwrap_throw();
+			let signal = controller.signal();
+			let mut request = gloo_net::http::Request::post(&self.url).abort_signal(Some(&signal));
+
+			for (name, value) in &self.headers {
+				request = request.header(name, value);
+			}
+
+			if let Some(token) = token {
+				request = request.header("Authorization", &format!("Bearer {token}"));
+			}
+
+			let request = request
+				.header("Content-Type", "application/json")
+				.body(body)?;
+			let response = AbortableRequest::new(request.send(), controller)
+				.await
+				.map_err(|error| ClientError::Transport(error.to_string()))?;
+
+			if !response.ok() {
+				let retry_after = response
+					.headers()
+					.get("retry-after")
+					.as_deref()
+					.and_then(parse_retry_after);
+				let message = response.text().await.unwrap_or_default();
+				metrics.response(response.status(), message.len());
+
+				return Err(HttpError {
+					status: response.status(),
+					message,
+					retry_after,
+				}
+				.into());
+			}
+
+			let text = response.text().await?;
+			metrics.response(response.status(), text.len());
+
+			parse_body(&text)
+		}
+
 		pub fn new(url: impl Into<String>) -> Self {
-			Self(url.into())
+			Self::builder().url(url).build()
 		}
 	}
 
@@ -245,3 +642,170 @@ pub const TESTNET: &str = "https://api.testnet.solana.com";
 pub const MAINNET: &str = "https://api.mainnet-beta.solana.com";
 pub const LOCALNET: &str = "http://127.0.0.1:8899";
 pub const DEBUG: &str = "http://34.90.18.145:8899";
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::AtomicU32;
+	use std::sync::atomic::Ordering;
+
+	use assert2::check;
+	use assert2::let_assert;
+	use serde_json::json;
+
+	use super::*;
+
+	#[test]
+	fn request_times_out() {
+		let timeout = Duration::from_millis(10);
+		let result: ClientResult<()> =
+			futures::executor::block_on(with_timeout(Some(timeout), futures::future::pending()));
+
+		let_assert!(Err(ClientError::Timeout(duration)) = result);
+		check!(duration == timeout);
+	}
+
+	#[cfg(feature = "ssr")]
+	#[test]
+	fn records_metrics_of_requests_which_time_out() -> anyhow::Result<()> {
+		// accepts connections without ever responding
+		let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+		let timeout = Duration::from_millis(50);
+		let provider = HttpProvider::builder()
+			.url(format!("http://{}", listener.local_addr()?))
+			.timeout(timeout)
+			.build();
+		let runtime = tokio::runtime::Builder::new_current_thread()
+			.enable_all()
+			.build()?;
+		crate::metrics::tests::take_requests();
+
+		let result = runtime.block_on(provider.send("getSlot", json!([])));
+
+		let_assert!(Err(ClientError::Timeout(_)) = result);
+		let events = crate::metrics::tests::take_requests();
+		let_assert!([event] = events.as_slice());
+		check!(event["method"] == "getSlot");
+		check!(event["status"] == Value::Null);
+		check!(event["error"] == ClientError::Timeout(timeout).to_string());
+
+		Ok(())
+	}
+
+	#[test]
+	fn request_completes_before_timeout() {
+		let result =
+			futures::executor::block_on(with_timeout(Some(Duration::from_secs(10)), async {
+				Ok(1)
+			}));
+
+		check!(let Ok(1) = result);
+	}
+
+	struct RotatingToken(AtomicU32);
+
+	#[async_trait]
+	impl AuthTokenProvider for RotatingToken {
+		async fn token(&self) -> ClientResult<String> {
+			Ok(format!("token-{}", self.0.load(Ordering::SeqCst)))
+		}
+
+		async fn refresh_token(&self) -> ClientResult<String> {
+			self.0.fetch_add(1, Ordering::SeqCst);
+			self.token().await
+		}
+	}
+
+	#[test]
+	fn refreshes_rejected_token() {
+		let token_provider: Arc<dyn AuthTokenProvider> = Arc::new(RotatingToken(AtomicU32::new(0)));
+		let tokens = std::sync::Mutex::new(vec![]);
+
+		let result = futures::executor::block_on(with_auth_token(Some(&token_provider), |token| {
+			let token = token.unwrap_or_default();
+			tokens.lock().unwrap().push(token.clone());
+
+			async move {
+				if token == "token-0" {
+					Err(ClientError::from(HttpError {
+						status: HttpError::UNAUTHORIZED,
+						message: String::new(),
+						retry_after: None,
+					}))
+				} else {
+					Ok(token)
+				}
+			}
+		}));
+
+		check!(let Ok("token-1") = result.as_deref());
+		check!(*tokens.lock().unwrap() == ["token-0", "token-1"]);
+	}
+
+	#[test]
+	fn collects_headers() {
+		let headers = collect_headers([("x-api-key", "secret")]);
+
+		check!(headers == [("x-api-key".to_string(), "secret".to_string())]);
+	}
+
+	#[test]
+	fn batch_request() {
+		let request = create_batch_request(vec![
+			("getSlot", Value::Null),
+			(
+				"getBalance",
+				json!(["83astBRguLMdt2h5U1Tpdq5tjFoJ6noeGwaY3mDLVcri"]),
+			),
+		]);
+
+		insta::assert_compact_json_snapshot!(request, @r###"
+  [
+    {
+      "jsonrpc": "2.0",
+      "id": 0,
+      "method": "getSlot"
+    },
+    {
+      "jsonrpc": "2.0",
+      "id": 1,
+      "method": "getBalance",
+      "params": [
+        "83astBRguLMdt2h5U1Tpdq5tjFoJ6noeGwaY3mDLVcri"
+      ]
+    }
+  ]
+  "###);
+	}
+
+	#[test]
+	fn batch_response_is_ordered_by_id() {
+		let response = json!([
+			{ "jsonrpc": "2.0", "result": 2, "id": 1 },
+			{ "jsonrpc": "2.0", "result": 1, "id": 0 },
+		]);
+		let responses = demultiplex_batch_response(2, response).unwrap();
+
+		check!(responses[0] == json!({ "jsonrpc": "2.0", "result": 1, "id": 0 }));
+		check!(responses[1] == json!({ "jsonrpc": "2.0", "result": 2, "id": 1 }));
+	}
+
+	#[test]
+	fn batch_response_missing_id() {
+		let response = json!([{ "jsonrpc": "2.0", "result": 1, "id": 0 }]);
+		let result = demultiplex_batch_response(2, response);
+
+		check!(let Err(ClientError::Other(_)) = result);
+	}
+
+	#[test]
+	fn batch_response_rejected() {
+		let response = json!({
+			"jsonrpc": "2.0",
+			"error": { "code": -32600, "message": "Invalid request" },
+			"id": 0
+		});
+		let result = demultiplex_batch_response(1, response);
+
+		check!(let Err(ClientError::Rpc(_)) = result);
+	}
+}
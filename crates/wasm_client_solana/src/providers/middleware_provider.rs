@@ -0,0 +1,398 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::RetryPolicy;
+use crate::RpcProvider;
+
+type SharedProvider = Arc<dyn RpcProvider + Send + Sync + 'static>;
+type SharedMiddleware = Arc<dyn RpcMiddleware + 'static>;
+
+/// A layer of the [`MiddlewareProvider`] which can inspect, rewrite or
+/// recover every request.
+///
+/// Most middleware only needs the hooks, which are run by the default
+/// [`RpcMiddleware::handle`]. Middleware which needs to control how the
+/// request is sent, e.g. to retry it, overrides `handle` instead.
+///
+/// The hooks receive the raw JSON-RPC response, so rpc errors reach
+/// [`RpcMiddleware::after_response`] in the `error` field of the response
+/// while [`RpcMiddleware::on_error`] is only called when the request failed,
+/// e.g. because of a transport failure or a timeout.
+///
+/// ```
+/// use async_trait::async_trait;
+/// use serde_json::Value;
+/// use wasm_client_solana::ClientResult;
+/// use wasm_client_solana::RpcMiddleware;
+///
+/// struct LoggingMiddleware;
+///
+/// #[async_trait]
+/// impl RpcMiddleware for LoggingMiddleware {
+/// 	async fn before_request(
+/// 		&self,
+/// 		method: &'static str,
+/// 		request: Value,
+/// 	) -> ClientResult<Value> {
+/// 		log::info!("sending {method}: {request}");
+/// 		Ok(request)
+/// 	}
+///
+/// 	async fn after_response(
+/// 		&self,
+/// 		method: &'static str,
+/// 		response: Value,
+/// 	) -> ClientResult<Value> {
+/// 		log::info!("received {method}: {response}");
+/// 		Ok(response)
+/// 	}
+/// }
+/// ```
+#[async_trait]
+pub trait RpcMiddleware: Send + Sync {
+	/// Called before the request is sent. The returned value replaces the
+	/// params of the request and an error aborts it.
+	async fn before_request(&self, _method: &'static str, request: Value) -> ClientResult<Value> {
+		Ok(request)
+	}
+	/// Called with the response of the request. The returned value replaces
+	/// the response.
+	async fn after_response(&self, _method: &'static str, response: Value) -> ClientResult<Value> {
+		Ok(response)
+	}
+	/// Called when the request failed. Returning a response recovers from the
+	/// `error`.
+	async fn on_error(&self, _method: &'static str, error: ClientError) -> ClientResult<Value> {
+		Err(error)
+	}
+	/// Handle the request by running the hooks around the remaining layers.
+	async fn handle(
+		&self,
+		method: &'static str,
+		request: Value,
+		next: Next<'_>,
+	) -> ClientResult<Value> {
+		let request = self.before_request(method, request).await?;
+
+		match next.run(method, request).await {
+			Ok(response) => self.after_response(method, response).await,
+			Err(error) => self.on_error(method, error).await,
+		}
+	}
+	/// Handle a batch of requests. The default implementation runs
+	/// [`RpcMiddleware::before_request`] and [`RpcMiddleware::after_response`]
+	/// for every request in the batch while the batch is still sent as a
+	/// single request. Errors of the batch are returned as is.
+	async fn handle_batch(
+		&self,
+		requests: Vec<(&'static str, Value)>,
+		next: Next<'_>,
+	) -> ClientResult<Vec<Value>> {
+		let mut rewritten = Vec::with_capacity(requests.len());
+
+		for (method, request) in requests {
+			rewritten.push((method, self.before_request(method, request).await?));
+		}
+
+		let methods = rewritten
+			.iter()
+			.map(|(method, _)| *method)
+			.collect::<Vec<_>>();
+		let responses = next.run_batch(rewritten).await?;
+		let mut result = Vec::with_capacity(responses.len());
+
+		for (method, response) in methods.into_iter().zip(responses) {
+			result.push(self.after_response(method, response).await?);
+		}
+
+		Ok(result)
+	}
+}
+
+/// Retry requests which fail with a retryable error as a middleware layer.
+#[async_trait]
+impl RpcMiddleware for RetryPolicy {
+	async fn handle(
+		&self,
+		method: &'static str,
+		request: Value,
+		next: Next<'_>,
+	) -> ClientResult<Value> {
+		self.retry(|| next.run(method, request.clone())).await
+	}
+
+	async fn handle_batch(
+		&self,
+		requests: Vec<(&'static str, Value)>,
+		next: Next<'_>,
+	) -> ClientResult<Vec<Value>> {
+		self.retry(|| next.run_batch(requests.clone())).await
+	}
+}
+
+/// The remaining layers of the [`MiddlewareProvider`] followed by the wrapped
+/// provider.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+	middlewares: &'a [SharedMiddleware],
+	provider: &'a (dyn RpcProvider + Send + Sync),
+}
+
+impl Next<'_> {
+	/// Send the request through the remaining layers.
+	pub async fn run(self, method: &'static str, request: Value) -> ClientResult<Value> {
+		match self.middlewares.split_first() {
+			Some((middleware, middlewares)) => {
+				let next = Next {
+					middlewares,
+					provider: self.provider,
+				};
+
+				middleware.handle(method, request, next).await
+			}
+			None => self.provider.send(method, request).await,
+		}
+	}
+
+	/// Send the batch through the remaining layers.
+	pub async fn run_batch(self, requests: Vec<(&'static str, Value)>) -> ClientResult<Vec<Value>> {
+		match self.middlewares.split_first() {
+			Some((middleware, middlewares)) => {
+				let next = Next {
+					middlewares,
+					provider: self.provider,
+				};
+
+				middleware.handle_batch(requests, next).await
+			}
+			None => self.provider.send_batch(requests).await,
+		}
+	}
+}
+
+/// An [`RpcProvider`] which sends every request through a stack of
+/// [`RpcMiddleware`] layers before it reaches the wrapped provider.
+///
+/// Layers are run in the order they were added, so the first layer sees the
+/// request first and the response last.
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use solana_sdk::commitment_config::CommitmentConfig;
+/// use wasm_client_solana::DEVNET;
+/// use wasm_client_solana::HttpProvider;
+/// use wasm_client_solana::MiddlewareProvider;
+/// use wasm_client_solana::RetryPolicy;
+/// use wasm_client_solana::SolanaRpcClient;
+///
+/// let provider = MiddlewareProvider::new(HttpProvider::new(DEVNET)).with(RetryPolicy::default());
+/// let rpc = SolanaRpcClient::new_with_provider(Arc::new(provider), CommitmentConfig::confirmed());
+/// ```
+#[derive(derive_more::Debug)]
+pub struct MiddlewareProvider {
+	#[debug(skip)]
+	provider: SharedProvider,
+	#[debug(skip)]
+	middlewares: Vec<SharedMiddleware>,
+}
+
+impl MiddlewareProvider {
+	pub fn new(provider: impl RpcProvider + Send + Sync + 'static) -> Self {
+		Self {
+			provider: Arc::new(provider),
+			middlewares: vec![],
+		}
+	}
+
+	/// Add the `middleware` as the innermost layer.
+	#[must_use]
+	pub fn with(mut self, middleware: impl RpcMiddleware + 'static) -> Self {
+		self.middlewares.push(Arc::new(middleware));
+		self
+	}
+
+	fn next(&self) -> Next<'_> {
+		Next {
+			middlewares: &self.middlewares,
+			provider: self.provider.as_ref(),
+		}
+	}
+}
+
+#[async_trait]
+impl RpcProvider for MiddlewareProvider {
+	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
+		self.next().run(method, request).await
+	}
+
+	async fn send_batch(&self, requests: Vec<(&'static str, Value)>) -> ClientResult<Vec<Value>> {
+		self.next().run_batch(requests).await
+	}
+
+	fn url(&self) -> String {
+		self.provider.url()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Mutex;
+	use std::time::Duration;
+
+	use assert2::check;
+	use assert2::let_assert;
+	use serde_json::json;
+
+	use super::*;
+	use crate::HttpError;
+	use crate::test_utils::TestProvider;
+
+	/// Fails the first `failures` requests and echoes the params of every
+	/// other request.
+	fn failing_provider(failures: u32) -> Arc<TestProvider> {
+		let failures = Mutex::new(failures);
+		let provider = TestProvider::new(move |_, request| {
+			let mut failures = failures.lock().unwrap();
+
+			if *failures > 0 {
+				*failures -= 1;
+
+				return Err(HttpError {
+					status: 503,
+					message: String::new(),
+					retry_after: None,
+				}
+				.into());
+			}
+
+			Ok(request.clone())
+		});
+
+		Arc::new(provider)
+	}
+
+	/// Records the order the hooks run in and rewrites the request.
+	struct RecordingMiddleware {
+		name: &'static str,
+		events: Arc<Mutex<Vec<String>>>,
+	}
+
+	#[async_trait]
+	impl RpcMiddleware for RecordingMiddleware {
+		async fn before_request(
+			&self,
+			method: &'static str,
+			request: Value,
+		) -> ClientResult<Value> {
+			self.events
+				.lock()
+				.unwrap()
+				.push(format!("{}:before:{method}", self.name));
+
+			Ok(json!([request, self.name]))
+		}
+
+		async fn after_response(
+			&self,
+			method: &'static str,
+			response: Value,
+		) -> ClientResult<Value> {
+			self.events
+				.lock()
+				.unwrap()
+				.push(format!("{}:after:{method}", self.name));
+
+			Ok(response)
+		}
+
+		async fn on_error(&self, method: &'static str, error: ClientError) -> ClientResult<Value> {
+			self.events
+				.lock()
+				.unwrap()
+				.push(format!("{}:error:{method}", self.name));
+
+			Err(error)
+		}
+	}
+
+	/// Wrap the `mock` with an `outer` and an `inner` recording layer.
+	fn recording_provider(
+		mock: &Arc<TestProvider>,
+		events: &Arc<Mutex<Vec<String>>>,
+	) -> MiddlewareProvider {
+		MiddlewareProvider::new(mock.clone())
+			.with(RecordingMiddleware {
+				name: "outer",
+				events: events.clone(),
+			})
+			.with(RecordingMiddleware {
+				name: "inner",
+				events: events.clone(),
+			})
+	}
+
+	#[test]
+	fn runs_layers_in_order() -> anyhow::Result<()> {
+		let mock = failing_provider(0);
+		let events = Arc::default();
+		let provider = recording_provider(&mock, &events);
+		let response = futures::executor::block_on(provider.send("getSlot", json!([])))?;
+
+		check!(response["result"] == json!([[[], "outer"], "inner"]));
+		check!(mock.requests("getSlot") == vec![json!([[[], "outer"], "inner"])]);
+		check!(
+			*events.lock().unwrap()
+				== vec![
+					"outer:before:getSlot",
+					"inner:before:getSlot",
+					"inner:after:getSlot",
+					"outer:after:getSlot",
+				]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn runs_error_hooks() {
+		let events = Arc::default();
+		let provider = recording_provider(&failing_provider(1), &events);
+		let result = futures::executor::block_on(provider.send("getSlot", json!([])));
+
+		let_assert!(Err(ClientError::Http(_)) = result);
+		check!(
+			*events.lock().unwrap()
+				== vec![
+					"outer:before:getSlot",
+					"inner:before:getSlot",
+					"inner:error:getSlot",
+					"outer:error:getSlot",
+				]
+		);
+	}
+
+	#[test]
+	fn retries_as_a_layer() -> anyhow::Result<()> {
+		let mock = failing_provider(2);
+		let policy = RetryPolicy::builder()
+			.initial_delay(Duration::from_millis(1))
+			.jitter(false)
+			.build();
+		let provider = MiddlewareProvider::new(mock.clone()).with(policy);
+		let responses = futures::executor::block_on(
+			provider.send_batch(vec![("getSlot", json!([])), ("getBalance", json!([1]))]),
+		)?;
+
+		check!(responses.len() == 2);
+		check!(responses[1]["result"] == json!([1]));
+		// each request failed once and was retried once
+		check!(mock.calls() == 4);
+
+		Ok(())
+	}
+}
@@ -1,5 +1,23 @@
+pub use caching_provider::CachingProvider;
+pub use caching_provider::DEFAULT_CACHE_TTLS;
+pub use failover_provider::EndpointStatus;
+pub use failover_provider::FailoverProvider;
+pub use failover_provider::RoutingStrategy;
 pub use http_provider::*;
+pub use middleware_provider::*;
+pub use retry::RetryPolicy;
+pub(crate) use retry::retry_with_policy;
+pub use websocket_connection::ConnectionState;
+pub(crate) use websocket_connection::ConnectionStateWatch;
+pub use websocket_connection::ReconnectConfig;
+pub use websocket_connection::WebSocketConfig;
+pub(crate) use websocket_connection::WebSocketConnection;
 pub use websocket_provider::*;
 
+mod caching_provider;
+mod failover_provider;
 mod http_provider;
+mod middleware_provider;
+mod retry;
+mod websocket_connection;
 mod websocket_provider;
@@ -0,0 +1,190 @@
+use std::future::Future;
+use std::time::Duration;
+
+use futures_timer::Delay;
+use typed_builder::TypedBuilder;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::utils::exponential_delay;
+use crate::utils::random_fraction;
+
+/// The policy used by the [`HttpProvider`](crate::HttpProvider) to retry
+/// requests which fail because of rate limits (`429`), server errors (`5xx`)
+/// or transport failures.
+///
+/// The delay between attempts grows exponentially unless the rpc provides a
+/// `Retry-After` header, in which case it is respected up to the
+/// `max_delay`.
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct RetryPolicy {
+	/// The maximum number of attempts, including the initial request.
+	#[builder(default = 3)]
+	pub max_attempts: u32,
+	/// The delay before the first retry.
+	#[builder(default = Duration::from_millis(250))]
+	pub initial_delay: Duration,
+	/// The upper bound for the delay between attempts.
+	#[builder(default = Duration::from_secs(10))]
+	pub max_delay: Duration,
+	/// The factor the delay is multiplied by after every failed attempt.
+	#[builder(default = 2.0)]
+	pub multiplier: f64,
+	/// Randomize each delay to between half and all of the backoff delay so
+	/// that many clients don't retry at the same moment.
+	#[builder(default = true)]
+	pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl RetryPolicy {
+	/// The delay before retrying the request which failed with the provided
+	/// `error` on the zero based `attempt`.
+	pub fn delay(&self, attempt: u32, error: &ClientError) -> Duration {
+		if let Some(retry_after) = error.retry_after() {
+			return retry_after.min(self.max_delay);
+		}
+
+		let delay = exponential_delay(self.initial_delay, self.multiplier, self.max_delay, attempt);
+
+		if self.jitter {
+			delay.mul_f64(0.5 + random_fraction() / 2.0)
+		} else {
+			delay
+		}
+	}
+
+	/// Run the `operation` until it succeeds, fails with an error which can't
+	/// be retried or the maximum number of attempts is reached.
+	pub async fn retry<T, F, Fut>(&self, mut operation: F) -> ClientResult<T>
+	where
+		F: FnMut() -> Fut,
+		Fut: Future<Output = ClientResult<T>>,
+	{
+		let mut attempt = 0;
+
+		loop {
+			match operation().await {
+				Err(error) if error.is_retryable() && attempt + 1 < self.max_attempts => {
+					let delay = self.delay(attempt, &error);
+					log::debug!("retrying request in {delay:?} after error: {error}");
+					Delay::new(delay).await;
+					attempt += 1;
+				}
+				result => return result,
+			}
+		}
+	}
+}
+
+/// Run the `operation` with the `policy` when it is provided.
+pub(crate) async fn retry_with_policy<T, F, Fut>(
+	policy: Option<&RetryPolicy>,
+	mut operation: F,
+) -> ClientResult<T>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = ClientResult<T>>,
+{
+	match policy {
+		Some(policy) => policy.retry(operation).await,
+		None => operation().await,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::AtomicU32;
+	use std::sync::atomic::Ordering;
+
+	use assert2::check;
+
+	use super::*;
+	use crate::HttpError;
+
+	fn http_error(status: u16, retry_after: Option<Duration>) -> ClientError {
+		HttpError {
+			status,
+			message: String::new(),
+			retry_after,
+		}
+		.into()
+	}
+
+	#[test]
+	fn delay() {
+		let policy = RetryPolicy::builder()
+			.initial_delay(Duration::from_millis(100))
+			.max_delay(Duration::from_millis(300))
+			.jitter(false)
+			.build();
+		let error = http_error(503, None);
+
+		check!(policy.delay(0, &error) == Duration::from_millis(100));
+		check!(policy.delay(1, &error) == Duration::from_millis(200));
+		check!(policy.delay(2, &error) == Duration::from_millis(300));
+
+		let error = http_error(429, Some(Duration::from_millis(250)));
+		check!(policy.delay(0, &error) == Duration::from_millis(250));
+	}
+
+	#[test]
+	fn retry_after_is_capped_by_max_delay() {
+		let policy = RetryPolicy::builder()
+			.max_delay(Duration::from_secs(10))
+			.build();
+		let error = http_error(429, Some(Duration::from_secs(3600)));
+
+		check!(policy.delay(0, &error) == Duration::from_secs(10));
+	}
+
+	#[test]
+	fn delay_with_jitter() {
+		let policy = RetryPolicy::builder()
+			.initial_delay(Duration::from_millis(100))
+			.build();
+		let error = ClientError::Transport("reset".into());
+
+		for _ in 0..20 {
+			let delay = policy.delay(0, &error);
+			check!(delay >= Duration::from_millis(50));
+			check!(delay <= Duration::from_millis(100));
+		}
+	}
+
+	#[test]
+	fn retries_until_max_attempts() {
+		let policy = RetryPolicy::builder()
+			.max_attempts(3)
+			.initial_delay(Duration::ZERO)
+			.build();
+		let attempts = AtomicU32::new(0);
+
+		let result: ClientResult<()> = futures::executor::block_on(policy.retry(|| {
+			attempts.fetch_add(1, Ordering::SeqCst);
+			async { Err(http_error(429, None)) }
+		}));
+
+		check!(let Err(ClientError::Http(_)) = result);
+		check!(attempts.load(Ordering::SeqCst) == 3);
+	}
+
+	#[test]
+	fn does_not_retry_other_errors() {
+		let policy = RetryPolicy::builder().initial_delay(Duration::ZERO).build();
+		let attempts = AtomicU32::new(0);
+
+		let result: ClientResult<()> = futures::executor::block_on(policy.retry(|| {
+			attempts.fetch_add(1, Ordering::SeqCst);
+			async { Err(http_error(400, None)) }
+		}));
+
+		check!(result.is_err());
+		check!(attempts.load(Ordering::SeqCst) == 1);
+	}
+}
@@ -0,0 +1,815 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::task::Waker;
+use std::task::ready;
+use std::time::Duration;
+
+use futures::FutureExt;
+use futures::Sink;
+use futures::Stream;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::channel::mpsc::UnboundedSender;
+use futures::channel::mpsc::unbounded;
+use futures::task::AtomicWaker;
+use futures_timer::Delay;
+use serde_json::Value;
+use serde_json::json;
+use typed_builder::TypedBuilder;
+
+use super::websocket_provider::WebSocketStream;
+use crate::ClientWebSocketError;
+use crate::SubscriptionId;
+use crate::metrics;
+use crate::utils::exponential_delay;
+use crate::utils::now;
+
+/// Configuration for the [`WebSocketProvider`](crate::WebSocketProvider).
+#[derive(Debug, Clone, Default, TypedBuilder)]
+pub struct WebSocketConfig {
+	/// When set the connection is reopened after it drops and every active
+	/// subscription is recreated on the new connection.
+	#[builder(default, setter(strip_option))]
+	pub reconnect: Option<ReconnectConfig>,
+	/// When set a `ping` message is sent at this interval while the
+	/// connection is open. This prevents proxies from closing idle
+	/// connections.
+	#[builder(default, setter(strip_option))]
+	pub heartbeat_interval: Option<Duration>,
+}
+
+/// The exponential backoff used when reconnecting a dropped websocket
+/// connection.
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct ReconnectConfig {
+	/// The maximum number of consecutive reconnection attempts before giving
+	/// up. When `None` the provider will keep trying forever.
+	#[builder(default, setter(strip_option))]
+	pub max_attempts: Option<u32>,
+	/// The delay before the first reconnection attempt.
+	#[builder(default = Duration::from_millis(500))]
+	pub initial_delay: Duration,
+	/// The upper bound for the delay between reconnection attempts.
+	#[builder(default = Duration::from_secs(30))]
+	pub max_delay: Duration,
+	/// The factor the delay is multiplied by after every failed attempt.
+	#[builder(default = 2.0)]
+	pub multiplier: f64,
+}
+
+impl Default for ReconnectConfig {
+	fn default() -> Self {
+		Self::builder().build()
+	}
+}
+
+impl ReconnectConfig {
+	/// The delay to wait before the reconnection attempt with the provided
+	/// zero based index.
+	pub fn delay(&self, attempt: u32) -> Duration {
+		exponential_delay(self.initial_delay, self.multiplier, self.max_delay, attempt)
+	}
+}
+
+/// The state of the websocket connection used by a
+/// [`WebSocketProvider`](crate::WebSocketProvider).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+	/// The connection is being opened for the first time.
+	Connecting,
+	/// The connection is open.
+	Open,
+	/// The connection is being closed.
+	Closing,
+	/// The connection is closed and will not be reopened.
+	Closed,
+	/// The connection dropped and is being reopened. The first attempt is `1`.
+	Reconnecting { attempt: u32 },
+}
+
+/// Shares the [`ConnectionState`] of a connection with every listener.
+#[derive(Debug)]
+pub(crate) struct ConnectionStateWatch {
+	inner: std::sync::Mutex<ConnectionStateInner>,
+}
+
+#[derive(Debug)]
+struct ConnectionStateInner {
+	state: ConnectionState,
+	listeners: Vec<UnboundedSender<ConnectionState>>,
+}
+
+impl ConnectionStateWatch {
+	pub(crate) fn new(state: ConnectionState) -> Self {
+		Self {
+			inner: std::sync::Mutex::new(ConnectionStateInner {
+				state,
+				listeners: Vec::new(),
+			}),
+		}
+	}
+
+	pub(crate) fn get(&self) -> ConnectionState {
+		self.inner
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.state
+	}
+
+	pub(crate) fn set(&self, state: ConnectionState) {
+		let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+
+		if inner.state == state {
+			return;
+		}
+
+		inner.state = state;
+		inner
+			.listeners
+			.retain(|listener| listener.unbounded_send(state).is_ok());
+	}
+
+	/// Listen to every change starting with the current state.
+	pub(crate) fn subscribe(&self) -> UnboundedReceiver<ConnectionState> {
+		let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+		let (sender, receiver) = unbounded();
+		let _ = sender.unbounded_send(inner.state);
+		inner.listeners.push(sender);
+
+		receiver
+	}
+}
+
+/// A websocket connection which keeps track of every subscription created
+/// through it. When a [`ReconnectConfig`] is provided, the underlying
+/// [`WebSocketStream`] is replaced after it ends and the active subscriptions
+/// are recreated. The new `subscription_id`s returned by the server are
+/// mapped back to the ids already held by each
+/// [`Subscription`](crate::Subscription).
+pub struct WebSocketConnection {
+	url: String,
+	reconnect: Option<ReconnectConfig>,
+	state: ConnectionStage,
+	/// The number of consecutive failed reconnection attempts.
+	attempt: u32,
+	/// Messages which must be sent before anything else, e.g. resubscriptions.
+	outbox: VecDeque<Value>,
+	/// Messages which are generated locally and yielded before anything read
+	/// from the stream.
+	inbox: VecDeque<Value>,
+	registry: SubscriptionRegistry,
+	/// Shared with the provider to report the state of the connection.
+	status: Arc<ConnectionStateWatch>,
+	/// Whether the current stream has been opened.
+	opened: bool,
+	heartbeat_interval: Option<Duration>,
+	heartbeat: Option<Delay>,
+	/// Both halves of the split connection can drive the reconnection so
+	/// every waker must be notified.
+	waker: Arc<SharedWaker>,
+}
+
+enum ConnectionStage {
+	Open(Pin<Box<WebSocketStream>>),
+	Waiting(Delay),
+	Closed,
+}
+
+impl WebSocketConnection {
+	pub(crate) fn new(
+		url: impl Into<String>,
+		config: WebSocketConfig,
+		status: Arc<ConnectionStateWatch>,
+	) -> Self {
+		let url = url.into();
+		let stream = Box::pin(WebSocketStream::new(&url));
+		status.set(ConnectionState::Connecting);
+
+		Self {
+			url,
+			reconnect: config.reconnect,
+			state: ConnectionStage::Open(stream),
+			attempt: 0,
+			outbox: VecDeque::new(),
+			inbox: VecDeque::new(),
+			registry: SubscriptionRegistry::default(),
+			status,
+			opened: false,
+			heartbeat_interval: config.heartbeat_interval,
+			heartbeat: None,
+			waker: Arc::default(),
+		}
+	}
+
+	fn open(&mut self) {
+		log::debug!("reconnecting websocket: {}", self.url);
+		self.state = ConnectionStage::Open(Box::pin(WebSocketStream::new(&self.url)));
+		self.outbox = self.registry.resubscribe().into();
+	}
+
+	fn opened(&mut self) {
+		if self.opened {
+			return;
+		}
+
+		self.opened = true;
+		self.heartbeat = self.heartbeat_interval.map(Delay::new);
+		self.status.set(ConnectionState::Open);
+	}
+
+	fn disconnected(&mut self) {
+		self.outbox.clear();
+		self.opened = false;
+		self.heartbeat = None;
+
+		let Some(reconnect) = &self.reconnect else {
+			self.closed();
+			return;
+		};
+
+		if reconnect
+			.max_attempts
+			.is_some_and(|max_attempts| self.attempt >= max_attempts)
+		{
+			log::warn!("websocket reconnection attempts exhausted: {}", self.url);
+			self.closed();
+			return;
+		}
+
+		let delay = reconnect.delay(self.attempt);
+		self.attempt += 1;
+		self.state = ConnectionStage::Waiting(Delay::new(delay));
+		self.status.set(ConnectionState::Reconnecting {
+			attempt: self.attempt,
+		});
+	}
+
+	fn closed(&mut self) {
+		self.state = ConnectionStage::Closed;
+		self.status.set(ConnectionState::Closed);
+	}
+
+	/// Check whether the current stream has finished opening.
+	fn poll_opened(&mut self, cx: &mut Context<'_>) {
+		if self.opened {
+			return;
+		}
+
+		let ConnectionStage::Open(stream) = &mut self.state else {
+			return;
+		};
+
+		if let Poll::Ready(Ok(())) = stream.as_mut().poll_ready(cx) {
+			self.opened();
+		}
+	}
+
+	/// Queue a `ping` message every time the heartbeat interval elapses.
+	fn poll_heartbeat(&mut self, cx: &mut Context<'_>) {
+		let (Some(interval), Some(heartbeat)) = (self.heartbeat_interval, &mut self.heartbeat)
+		else {
+			return;
+		};
+
+		if heartbeat.poll_unpin(cx).is_ready() {
+			self.outbox
+				.push_back(json!({ "jsonrpc": "2.0", "method": "ping" }));
+			heartbeat.reset(interval);
+			// register the waker for the next interval
+			let _ = heartbeat.poll_unpin(cx);
+		}
+	}
+
+	/// Drive the connection until it is either open or closed.
+	fn poll_open(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), ClientWebSocketError>> {
+		loop {
+			match &mut self.state {
+				ConnectionStage::Open(_) => return Poll::Ready(Ok(())),
+				ConnectionStage::Closed => {
+					return Poll::Ready(Err(ClientWebSocketError::ConnectionClose));
+				}
+				ConnectionStage::Waiting(delay) => {
+					ready!(delay.poll_unpin(cx));
+					self.open();
+				}
+			}
+		}
+	}
+
+	/// Push every queued message into the open stream.
+	fn poll_outbox(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), ClientWebSocketError>> {
+		let ConnectionStage::Open(stream) = &mut self.state else {
+			return Poll::Ready(Ok(()));
+		};
+
+		if self.outbox.is_empty() {
+			return Poll::Ready(Ok(()));
+		}
+
+		while !self.outbox.is_empty() {
+			ready!(stream.as_mut().poll_ready(cx))?;
+			let Some(value) = self.outbox.pop_front() else {
+				break;
+			};
+			stream.as_mut().start_send(value)?;
+		}
+
+		stream.as_mut().poll_flush(cx)
+	}
+}
+
+impl Stream for WebSocketConnection {
+	type Item = Result<Value, ClientWebSocketError>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		this.waker.reader.register(cx.waker());
+		let waker = Waker::from(this.waker.clone());
+		let mut cx = Context::from_waker(&waker);
+
+		loop {
+			if let Some(value) = this.inbox.pop_front() {
+				return Poll::Ready(Some(Ok(value)));
+			}
+
+			if ready!(this.poll_open(&mut cx)).is_err() {
+				return Poll::Ready(None);
+			}
+
+			this.poll_opened(&mut cx);
+			this.poll_heartbeat(&mut cx);
+
+			if let Poll::Ready(Err(error)) = this.poll_outbox(&mut cx) {
+				log::warn!("failed to send queued websocket message: {error}");
+			}
+
+			let ConnectionStage::Open(stream) = &mut this.state else {
+				continue;
+			};
+
+			match ready!(stream.as_mut().poll_next(&mut cx)) {
+				Some(Ok(value)) => {
+					this.attempt = 0;
+					this.opened();
+
+					if let Some(value) = this.registry.receive(value) {
+						return Poll::Ready(Some(Ok(value)));
+					}
+				}
+				Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+				None => this.disconnected(),
+			}
+		}
+	}
+}
+
+impl Sink<Value> for WebSocketConnection {
+	type Error = ClientWebSocketError;
+
+	fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		let this = self.get_mut();
+		this.waker.writer.register(cx.waker());
+		let waker = Waker::from(this.waker.clone());
+		let mut cx = Context::from_waker(&waker);
+
+		ready!(this.poll_open(&mut cx))?;
+		ready!(this.poll_outbox(&mut cx))?;
+
+		let ConnectionStage::Open(stream) = &mut this.state else {
+			return Poll::Ready(Err(ClientWebSocketError::ConnectionClose));
+		};
+
+		ready!(stream.as_mut().poll_ready(&mut cx))?;
+		this.opened();
+
+		Poll::Ready(Ok(()))
+	}
+
+	fn start_send(self: Pin<&mut Self>, item: Value) -> Result<(), Self::Error> {
+		let this = self.get_mut();
+
+		let Some(item) = this.registry.send(item, &mut this.inbox) else {
+			this.waker.wake_by_ref();
+			return Ok(());
+		};
+
+		let ConnectionStage::Open(stream) = &mut this.state else {
+			return Err(ClientWebSocketError::ConnectionClose);
+		};
+
+		stream.as_mut().start_send(item)
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		let this = self.get_mut();
+		this.waker.writer.register(cx.waker());
+		let waker = Waker::from(this.waker.clone());
+		let mut cx = Context::from_waker(&waker);
+
+		let ConnectionStage::Open(stream) = &mut this.state else {
+			return Poll::Ready(Ok(()));
+		};
+
+		stream.as_mut().poll_flush(&mut cx)
+	}
+
+	fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		let this = self.get_mut();
+		this.waker.writer.register(cx.waker());
+		let waker = Waker::from(this.waker.clone());
+		let mut cx = Context::from_waker(&waker);
+
+		let ConnectionStage::Open(stream) = &mut this.state else {
+			return Poll::Ready(Ok(()));
+		};
+
+		this.status.set(ConnectionState::Closing);
+		let result = ready!(stream.as_mut().poll_close(&mut cx));
+		this.closed();
+
+		Poll::Ready(result)
+	}
+}
+
+impl Drop for WebSocketConnection {
+	fn drop(&mut self) {
+		self.status.set(ConnectionState::Closed);
+	}
+}
+
+/// Wakes the tasks polling both the reading and writing half of the
+/// connection.
+#[derive(Default)]
+struct SharedWaker {
+	reader: AtomicWaker,
+	writer: AtomicWaker,
+}
+
+impl Wake for SharedWaker {
+	fn wake(self: Arc<Self>) {
+		self.wake_by_ref();
+	}
+
+	fn wake_by_ref(self: &Arc<Self>) {
+		self.reader.wake();
+		self.writer.wake();
+	}
+}
+
+/// A subscribe request sent through the connection.
+#[derive(Debug, Clone)]
+struct SubscriptionEntry {
+	/// The original subscribe request which is resent on reconnection.
+	request: Value,
+	/// The `subscription_id` exposed to the
+	/// [`Subscription`](crate::Subscription).
+	local: Option<SubscriptionId>,
+	/// The `subscription_id` used by the current server connection.
+	server: Option<SubscriptionId>,
+	/// The number of notifications received by the subscription.
+	notifications: u64,
+	/// The time the subscription was confirmed by the server.
+	subscribed_at: Duration,
+}
+
+impl SubscriptionEntry {
+	fn method(&self) -> &str {
+		self.request
+			.get("method")
+			.and_then(Value::as_str)
+			.unwrap_or_default()
+	}
+}
+
+/// Tracks subscriptions by the `id` of the request which created them.
+#[derive(Debug, Default)]
+struct SubscriptionRegistry {
+	entries: HashMap<u64, SubscriptionEntry>,
+	/// Used to allocate a `local` id when the id returned by the server is
+	/// already used by an existing subscription.
+	next_local: Option<SubscriptionId>,
+}
+
+impl SubscriptionRegistry {
+	/// Inspect an outgoing message. Returns `None` when the message was
+	/// handled locally and should not be sent.
+	fn send(&mut self, mut value: Value, inbox: &mut VecDeque<Value>) -> Option<Value> {
+		let Some(method) = value.get("method").and_then(Value::as_str) else {
+			return Some(value);
+		};
+		let Some(id) = value.get("id").and_then(Value::as_u64) else {
+			return Some(value);
+		};
+
+		if method.ends_with("Unsubscribe") {
+			let Some(local) = value.pointer("/params/0").and_then(Value::as_u64) else {
+				return Some(value);
+			};
+			let Some(key) = self.find(|entry| entry.local == Some(local)) else {
+				return Some(value);
+			};
+			let entry = self.entries.remove(&key)?;
+			self.record_removed(&entry);
+
+			// The subscription is still being recreated so there is nothing to
+			// remove from the server.
+			let Some(server) = entry.server else {
+				inbox.push_back(json!({ "jsonrpc": "2.0", "id": id, "result": true }));
+				return None;
+			};
+
+			if let Some(param) = value.pointer_mut("/params/0") {
+				*param = server.into();
+			}
+		} else if method.ends_with("Subscribe") {
+			self.entries.insert(
+				id,
+				SubscriptionEntry {
+					request: value.clone(),
+					local: None,
+					server: None,
+					notifications: 0,
+					subscribed_at: Duration::ZERO,
+				},
+			);
+		}
+
+		Some(value)
+	}
+
+	/// Inspect an incoming message. Returns `None` when the message was
+	/// consumed by the registry.
+	fn receive(&mut self, mut value: Value) -> Option<Value> {
+		if let Some(subscription) = value
+			.pointer("/params/subscription")
+			.and_then(Value::as_u64)
+		{
+			let key = self.find(|entry| entry.server == Some(subscription))?;
+			let entry = self.entries.get_mut(&key)?;
+			let local = entry.local?;
+			entry.notifications += 1;
+			metrics::record_notification(
+				entry.method(),
+				local,
+				entry.notifications,
+				entry.subscribed_at,
+			);
+
+			if let Some(param) = value.pointer_mut("/params/subscription") {
+				*param = local.into();
+			}
+
+			// signature subscriptions are removed by the server after the final
+			// notification
+			let method = value.get("method").and_then(Value::as_str);
+			let result = value.pointer("/params/result/value");
+			if method == Some("signatureNotification")
+				&& result.and_then(Value::as_str) != Some("receivedSignature")
+			{
+				if let Some(entry) = self.entries.remove(&key) {
+					self.record_removed(&entry);
+				}
+			}
+
+			return Some(value);
+		}
+
+		let Some(id) = value.get("id").and_then(Value::as_u64) else {
+			return Some(value);
+		};
+		let Some(entry) = self.entries.get(&id) else {
+			return Some(value);
+		};
+		let resubscribing = entry.local.is_some();
+
+		if value.get("error").is_some() {
+			if let Some(entry) = self.entries.remove(&id) {
+				self.record_removed(&entry);
+			}
+
+			if resubscribing {
+				log::warn!("failed to recreate subscription: {value}");
+				return None;
+			}
+
+			return Some(value);
+		}
+
+		let server = value.get("result").and_then(Value::as_u64)?;
+
+		if resubscribing {
+			if let Some(entry) = self.entries.get_mut(&id) {
+				entry.server = Some(server);
+			}
+
+			return None;
+		}
+
+		let local = if self.find(|entry| entry.local == Some(server)).is_some() {
+			self.allocate_local()
+		} else {
+			server
+		};
+
+		if let Some(entry) = self.entries.get_mut(&id) {
+			entry.local = Some(local);
+			entry.server = Some(server);
+			entry.subscribed_at = now();
+		}
+
+		if let Some(entry) = self.entries.get(&id) {
+			metrics::record_subscription(entry.method(), true, self.active());
+		}
+
+		if let Some(result) = value.get_mut("result") {
+			*result = local.into();
+		}
+
+		Some(value)
+	}
+
+	/// The requests which recreate every subscription on a new connection.
+	fn resubscribe(&mut self) -> Vec<Value> {
+		let mut entries = self.entries.iter_mut().collect::<Vec<_>>();
+		entries.sort_by_key(|(id, _)| **id);
+		entries
+			.into_iter()
+			.map(|(_, entry)| {
+				entry.server = None;
+				entry.request.clone()
+			})
+			.collect()
+	}
+
+	/// The number of subscriptions which were confirmed by the server.
+	fn active(&self) -> usize {
+		self.entries
+			.values()
+			.filter(|entry| entry.local.is_some())
+			.count()
+	}
+
+	/// Record the removal of a subscription which was confirmed by the
+	/// server.
+	fn record_removed(&self, entry: &SubscriptionEntry) {
+		if entry.local.is_some() {
+			metrics::record_subscription(entry.method(), false, self.active());
+		}
+	}
+
+	fn find(&self, predicate: impl Fn(&SubscriptionEntry) -> bool) -> Option<u64> {
+		self.entries
+			.iter()
+			.find_map(|(id, entry)| predicate(entry).then_some(*id))
+	}
+
+	fn allocate_local(&mut self) -> SubscriptionId {
+		let mut local = self.next_local.unwrap_or(SubscriptionId::MAX);
+
+		while self.find(|entry| entry.local == Some(local)).is_some() {
+			local -= 1;
+		}
+
+		self.next_local = Some(local - 1);
+		local
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+
+	use super::*;
+
+	fn subscribe(id: u64) -> Value {
+		json!({ "jsonrpc": "2.0", "id": id, "method": "accountSubscribe", "params": [] })
+	}
+
+	fn notification(subscription: u64) -> Value {
+		json!({
+			"jsonrpc": "2.0",
+			"method": "accountNotification",
+			"params": { "result": {}, "subscription": subscription }
+		})
+	}
+
+	#[test]
+	fn reconnect_delay() {
+		let config = ReconnectConfig::builder()
+			.initial_delay(Duration::from_millis(100))
+			.max_delay(Duration::from_secs(1))
+			.build();
+
+		check!(config.delay(0) == Duration::from_millis(100));
+		check!(config.delay(1) == Duration::from_millis(200));
+		check!(config.delay(3) == Duration::from_millis(800));
+		check!(config.delay(4) == Duration::from_secs(1));
+		check!(config.delay(100) == Duration::from_secs(1));
+		check!(config.delay(u32::MAX) == Duration::from_secs(1));
+
+		// the default config never gives up
+		let config = ReconnectConfig::default();
+		check!(config.delay(100) == config.max_delay);
+	}
+
+	#[test]
+	fn connection_state_watch() {
+		let watch = ConnectionStateWatch::new(ConnectionState::Connecting);
+		let mut receiver = watch.subscribe();
+
+		watch.set(ConnectionState::Open);
+		watch.set(ConnectionState::Open);
+		watch.set(ConnectionState::Reconnecting { attempt: 1 });
+		drop(watch);
+
+		let states = futures::executor::block_on_stream(&mut receiver).collect::<Vec<_>>();
+		check!(
+			states
+				== vec![
+					ConnectionState::Connecting,
+					ConnectionState::Open,
+					ConnectionState::Reconnecting { attempt: 1 },
+				]
+		);
+	}
+
+	#[test]
+	fn registry_maps_resubscriptions() {
+		let mut registry = SubscriptionRegistry::default();
+		let mut inbox = VecDeque::new();
+
+		registry.send(subscribe(1000), &mut inbox);
+		let response = registry.receive(json!({ "jsonrpc": "2.0", "id": 1000, "result": 5 }));
+		check!(response == Some(json!({ "jsonrpc": "2.0", "id": 1000, "result": 5 })));
+		check!(registry.receive(notification(5)) == Some(notification(5)));
+
+		let requests = registry.resubscribe();
+		check!(requests == vec![subscribe(1000)]);
+
+		// the resubscribe response is consumed
+		check!(
+			registry
+				.receive(json!({ "jsonrpc": "2.0", "id": 1000, "result": 0 }))
+				.is_none()
+		);
+		check!(registry.receive(notification(0)) == Some(notification(5)));
+		check!(registry.entries[&1000].notifications == 2);
+		check!(registry.active() == 1);
+
+		// the original id is rewritten to the new server id
+		let unsubscribe =
+			json!({ "jsonrpc": "2.0", "id": 1000, "method": "accountUnsubscribe", "params": [5] });
+		check!(
+			registry.send(unsubscribe, &mut inbox)
+				== Some(json!({
+					"jsonrpc": "2.0",
+					"id": 1000,
+					"method": "accountUnsubscribe",
+					"params": [0]
+				}))
+		);
+		check!(registry.entries.is_empty());
+		check!(registry.receive(notification(0)).is_none());
+	}
+
+	#[test]
+	fn registry_avoids_colliding_ids() {
+		let mut registry = SubscriptionRegistry::default();
+		let mut inbox = VecDeque::new();
+
+		registry.send(subscribe(1000), &mut inbox);
+		registry.receive(json!({ "jsonrpc": "2.0", "id": 1000, "result": 0 }));
+		registry.resubscribe();
+		registry.receive(json!({ "jsonrpc": "2.0", "id": 1000, "result": 1 }));
+
+		registry.send(subscribe(1001), &mut inbox);
+		let response = registry.receive(json!({ "jsonrpc": "2.0", "id": 1001, "result": 0 }));
+		check!(
+			response
+				== Some(json!({ "jsonrpc": "2.0", "id": 1001, "result": SubscriptionId::MAX }))
+		);
+		check!(registry.receive(notification(0)) == Some(notification(SubscriptionId::MAX)));
+		check!(registry.receive(notification(1)) == Some(notification(0)));
+	}
+
+	#[test]
+	fn registry_unsubscribes_while_reconnecting() {
+		let mut registry = SubscriptionRegistry::default();
+		let mut inbox = VecDeque::new();
+
+		registry.send(subscribe(1000), &mut inbox);
+		registry.receive(json!({ "jsonrpc": "2.0", "id": 1000, "result": 3 }));
+		registry.resubscribe();
+
+		let unsubscribe =
+			json!({ "jsonrpc": "2.0", "id": 1000, "method": "accountUnsubscribe", "params": [3] });
+		check!(registry.send(unsubscribe, &mut inbox).is_none());
+		check!(inbox.pop_front() == Some(json!({ "jsonrpc": "2.0", "id": 1000, "result": true })));
+		check!(registry.entries.is_empty());
+	}
+}
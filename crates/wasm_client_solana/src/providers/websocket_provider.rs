@@ -1,7 +1,9 @@
+use std::collections::HashSet;
 use std::hash::Hash;
 use std::marker::PhantomData;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::PoisonError;
 use std::task::Context;
 use std::task::Poll;
 use std::task::ready;
@@ -21,9 +23,13 @@ use serde_json::Value;
 use typed_builder::TypedBuilder;
 
 #[cfg(feature = "ssr")]
-use self::websocket_provider_reqwest::*;
+pub(super) use self::websocket_provider_reqwest::*;
 #[cfg(not(feature = "ssr"))]
-use self::websocket_provider_wasm::*;
+pub(super) use self::websocket_provider_wasm::*;
+use super::ConnectionState;
+use super::ConnectionStateWatch;
+use super::WebSocketConfig;
+use super::WebSocketConnection;
 use crate::ClientRequest;
 use crate::ClientWebSocketError;
 use crate::SubscriptionId;
@@ -52,45 +58,200 @@ where
 	}
 }
 
+type WebSocketSender = Arc<Mutex<SplitSink<WebSocketConnection, Value>>>;
+type WebSocketReceiver = Forked<SplitStream<WebSocketConnection>>;
+
+/// The shared halves of an open websocket connection. The connection is
+/// closed once every clone has been dropped.
+#[derive(Clone)]
+struct WebSocketHandles {
+	sender: WebSocketSender,
+	receiver: WebSocketReceiver,
+}
+
+struct WeakWebSocketHandles {
+	sender: std::sync::Weak<Mutex<SplitSink<WebSocketConnection, Value>>>,
+	receiver: fork_stream::Weak<SplitStream<WebSocketConnection>>,
+}
+
+impl WeakWebSocketHandles {
+	fn upgrade(&self) -> Option<WebSocketHandles> {
+		Some(WebSocketHandles {
+			sender: self.sender.upgrade()?,
+			receiver: self.receiver.upgrade()?,
+		})
+	}
+}
+
+/// Subscriptions created with [`WebSocketProvider::create_subscription`]
+/// which haven't been attached to a [`Subscription`] yet. The handles keep
+/// the connection open until then.
+#[derive(Default)]
+struct PendingSubscriptions {
+	ids: HashSet<u32>,
+	handles: Option<WebSocketHandles>,
+}
+
+/// The websocket connection is only opened when the first subscription is
+/// created and is closed once every [`Subscription`] and [`Unsubscription`]
+/// has been dropped.
 #[derive(Clone, derive_more::Debug)]
 pub struct WebSocketProvider {
 	/// The websocket url.
 	url: String,
 	/// The client ID which identifies current client ID.
 	id: Arc<std::sync::Mutex<u32>>,
+	config: WebSocketConfig,
+	/// The currently open connection which is shared by all subscriptions.
+	#[debug(skip)]
+	connection: Arc<std::sync::Mutex<Option<WeakWebSocketHandles>>>,
 	#[debug(skip)]
-	sender: Arc<Mutex<SplitSink<WebSocketStream, Value>>>,
+	pending: Arc<std::sync::Mutex<PendingSubscriptions>>,
 	#[debug(skip)]
-	receiver: Forked<SplitStream<WebSocketStream>>,
+	status: Arc<ConnectionStateWatch>,
 }
 
 impl WebSocketProvider {
 	pub fn new(url: impl Into<String>) -> Self {
-		let url = get_ws_url(url);
-		let stream = WebSocketStream::new(&url);
-		let (sink, stream) = stream.split();
-		let receiver = stream.fork();
-		let sender = Arc::new(Mutex::new(sink));
+		Self::new_with_config(url, WebSocketConfig::default())
+	}
 
+	/// Create a provider with a custom [`WebSocketConfig`].
+	///
+	/// ```
+	/// use wasm_client_solana::DEVNET;
+	/// use wasm_client_solana::ReconnectConfig;
+	/// use wasm_client_solana::WebSocketConfig;
+	/// use wasm_client_solana::WebSocketProvider;
+	///
+	/// # fn run() {
+	/// let config = WebSocketConfig::builder()
+	/// 	.reconnect(ReconnectConfig::default())
+	/// 	.build();
+	/// let provider = WebSocketProvider::new_with_config(DEVNET, config);
+	/// # }
+	/// ```
+	pub fn new_with_config(url: impl Into<String>, config: WebSocketConfig) -> Self {
 		Self {
-			url,
+			url: get_ws_url(url),
 			// start with 1000 since the default id used for http methods is 0
 			id: Arc::new(std::sync::Mutex::new(1000)),
-			sender,
-			receiver,
+			config,
+			connection: Arc::default(),
+			pending: Arc::default(),
+			status: Arc::new(ConnectionStateWatch::new(ConnectionState::Closed)),
 		}
 	}
 
+	/// Get the open connection or open a new one.
+	fn connect(&self) -> WebSocketHandles {
+		let mut connection = self
+			.connection
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner);
+
+		if let Some(handles) = connection.as_ref().and_then(WeakWebSocketHandles::upgrade) {
+			return handles;
+		}
+
+		let stream = WebSocketConnection::new(&self.url, self.config.clone(), self.status.clone());
+		let (sink, stream) = stream.split();
+		let handles = WebSocketHandles {
+			sender: Arc::new(Mutex::new(sink)),
+			receiver: stream.fork(),
+		};
+
+		*connection = Some(WeakWebSocketHandles {
+			sender: Arc::downgrade(&handles.sender),
+			receiver: handles.receiver.downgrade(),
+		});
+
+		handles
+	}
+
+	/// Get the connection for the subscription with the `id` and stop keeping
+	/// the connection open for it.
+	fn attach(&self, id: u32) -> WebSocketHandles {
+		let handles = self.connect();
+		let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+		pending.ids.remove(&id);
+
+		if pending.ids.is_empty() {
+			pending.handles = None;
+		}
+
+		handles
+	}
+
 	pub fn url(&self) -> &str {
 		&self.url
 	}
 
+	/// The current [`ConnectionState`] of the websocket connection.
+	pub fn connection_state(&self) -> ConnectionState {
+		self.status.get()
+	}
+
+	/// A stream of [`ConnectionState`] changes which starts with the current
+	/// state.
+	///
+	/// ```
+	/// use futures::StreamExt;
+	/// use wasm_client_solana::ConnectionState;
+	/// use wasm_client_solana::WebSocketProvider;
+	///
+	/// # async fn run(provider: WebSocketProvider) {
+	/// let mut states = provider.connection_state_stream();
+	///
+	/// while let Some(state) = states.next().await {
+	/// 	if let ConnectionState::Reconnecting { attempt } = state {
+	/// 		log::warn!("reconnecting: attempt {attempt}");
+	/// 	}
+	/// }
+	/// # }
+	/// ```
+	pub fn connection_state_stream(&self) -> impl Stream<Item = ConnectionState> + Unpin + 'static {
+		self.status.subscribe()
+	}
+
 	/// Create a subscription and return the `id` used to create the
 	/// subscription and `subscription_id` once a response is received.
+	///
+	/// The connection is kept open until the [`Subscription`] is created with
+	/// [`Subscription::new`] and then for as long as it is used.
 	pub async fn create_subscription<T: WebSocketMethod>(
 		&self,
 		params: T,
 	) -> Result<(u32, SubscriptionId), ClientWebSocketError> {
+		let (id, subscription_id, handles) = self.send_subscription(params).await?;
+		let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+		pending.ids.insert(id);
+		pending.handles = Some(handles);
+
+		Ok((id, subscription_id))
+	}
+
+	/// Create a subscription which keeps the connection open until it is
+	/// dropped.
+	pub(crate) async fn subscribe<T, N>(
+		&self,
+		params: T,
+	) -> Result<Subscription<N>, ClientWebSocketError>
+	where
+		T: WebSocketMethod,
+		N: DeserializeOwned + WebSocketNotification,
+	{
+		let (id, subscription_id, handles) = self.send_subscription(params).await?;
+
+		Ok(Subscription::from_handles(handles, id, subscription_id))
+	}
+
+	/// Send the subscription request and return the handles of the
+	/// connection it was sent on along with the `id` and `subscription_id`.
+	async fn send_subscription<T: WebSocketMethod>(
+		&self,
+		params: T,
+	) -> Result<(u32, SubscriptionId, WebSocketHandles), ClientWebSocketError> {
 		let id = self.next_id()?;
 		let request = ClientRequest::builder()
 			.method(T::SUBSCRIBE)
@@ -98,16 +259,19 @@ impl WebSocketProvider {
 			.id(id)
 			.build()
 			.try_to_value()?;
+		let handles = self.connect();
+		// fork the receiver before sending so the response can't be missed
+		let receiver = handles.receiver.clone();
 
 		// immediately drop the lock at the end of this block
 		{
-			let mut lock = self.sender.lock().await;
+			let mut lock = handles.sender.lock().await;
 			lock.send(request)
 				.await
 				.map_err(|_| ClientWebSocketError::MessageSendError)?;
 		}
 
-		let mut stream = self.receiver.clone().filter_map(|value| {
+		let mut stream = receiver.filter_map(|value| {
 			let Ok(value) = value else {
 				return future::ready(None);
 			};
@@ -123,7 +287,7 @@ impl WebSocketProvider {
 			return Err(ClientWebSocketError::Subscription);
 		};
 
-		Ok((id, response.result))
+		Ok((id, response.result, handles))
 	}
 
 	fn next_id(&self) -> Result<u32, ClientWebSocketError> {
@@ -144,9 +308,9 @@ pub struct Unsubscription {
 	/// The name of the method used to unsubscribe.
 	pub(crate) method: &'static str,
 	/// The shared sink for pushing messages into the websocket stream.
-	pub(crate) sender: Arc<Mutex<SplitSink<WebSocketStream, Value>>>,
+	pub(crate) sender: WebSocketSender,
 	/// The shared receiver for websocket messages.
-	pub(crate) receiver: Forked<SplitStream<WebSocketStream>>,
+	pub(crate) receiver: WebSocketReceiver,
 	/// The `id` that was originally used to create the parent subscription.
 	pub(crate) id: u32,
 	/// The `subscription_id` used to unsubscribe.
@@ -211,9 +375,9 @@ impl Unsubscription {
 pub struct Subscription<T: DeserializeOwned + WebSocketNotification> {
 	/// The shared receiver for receiving messages.
 	#[pin]
-	pub(crate) receiver: Forked<SplitStream<WebSocketStream>>,
+	pub(crate) receiver: WebSocketReceiver,
 	/// The shared sink for pushing messages into the websocket stream.
-	pub(crate) sender: Arc<Mutex<SplitSink<WebSocketStream, Value>>>,
+	pub(crate) sender: WebSocketSender,
 	#[builder(default)]
 	pub(crate) latest: PhantomData<T>,
 	/// The `id` that was originally used to create the parent subscription.
@@ -224,10 +388,18 @@ pub struct Subscription<T: DeserializeOwned + WebSocketNotification> {
 }
 
 impl<T: DeserializeOwned + WebSocketNotification> Subscription<T> {
+	/// Attach to a subscription on the connection of the `ws` provider, which
+	/// is opened when it has been closed.
 	pub fn new(ws: &WebSocketProvider, id: u32, subscription_id: SubscriptionId) -> Self {
+		Self::from_handles(ws.attach(id), id, subscription_id)
+	}
+
+	fn from_handles(handles: WebSocketHandles, id: u32, subscription_id: SubscriptionId) -> Self {
+		let WebSocketHandles { sender, receiver } = handles;
+
 		Self::builder()
-			.receiver(ws.receiver.clone())
-			.sender(ws.sender.clone())
+			.receiver(receiver)
+			.sender(sender)
 			.id(id)
 			.subscription_id(subscription_id)
 			.build()
@@ -305,26 +477,99 @@ impl<T: DeserializeOwned + WebSocketNotification> Stream for Subscription<T> {
 	type Item = SubscriptionResponse<T>;
 
 	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-		let subscription_id = self.get_unsubscription().subscription_id;
+		let subscription_id = self.subscription_id;
 		let mut this = self.project();
 
-		let Some(result) = ready!(this.receiver.as_mut().poll_next(cx)) else {
-			return Poll::Ready(None);
-		};
+		// keep reading until a matching notification is found so that the waker
+		// is always registered with the receiver before returning pending
+		loop {
+			let Some(result) = ready!(this.receiver.as_mut().poll_next(cx)) else {
+				return Poll::Ready(None);
+			};
 
-		let Ok(value) = result else {
-			return Poll::Pending;
-		};
+			let Ok(value) = result else {
+				continue;
+			};
 
-		let Some(json) = serde_json::from_value::<SubscriptionResponse<T>>(value).ok() else {
-			return Poll::Pending;
-		};
+			let Ok(json) = serde_json::from_value::<SubscriptionResponse<T>>(value) else {
+				continue;
+			};
+
+			if json.method != T::NOTIFICATION || json.params.subscription != subscription_id {
+				continue;
+			}
+
+			return Poll::Ready(Some(json));
+		}
+	}
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+	use assert2::check;
+
+	use super::*;
+	use crate::rpc_response::SlotInfo;
+
+	#[test]
+	fn connection_is_lazy() {
+		let provider = WebSocketProvider::new("http://127.0.0.1:8899");
+		check!(provider.connection_state() == ConnectionState::Closed);
+
+		let handles = provider.connect();
+		check!(provider.connection_state() == ConnectionState::Connecting);
+
+		// clones share the same connection
+		let clone = provider.clone().connect();
+		check!(Arc::ptr_eq(&handles.sender, &clone.sender));
+
+		drop(handles);
+		drop(clone);
+		check!(provider.connection_state() == ConnectionState::Closed);
+	}
+
+	#[test]
+	fn subscriptions_keep_the_connection_open() {
+		let provider = WebSocketProvider::new("http://127.0.0.1:8899");
+		let subscription = Subscription::<SlotInfo>::from_handles(provider.connect(), 1000, 1);
+		let attached = Subscription::<SlotInfo>::new(&provider, 1001, 2);
+		check!(Arc::ptr_eq(&subscription.sender, &attached.sender));
+
+		// no handles are kept by the provider once the subscriptions are dropped
+		drop(subscription);
+		check!(provider.connection_state() == ConnectionState::Connecting);
+		drop(attached);
+		check!(provider.connection_state() == ConnectionState::Closed);
+
+		// a new connection is opened when none is open
+		let subscription = Subscription::<SlotInfo>::new(&provider, 1002, 3);
+		check!(provider.connection_state() == ConnectionState::Connecting);
+		drop(subscription);
+		check!(provider.connection_state() == ConnectionState::Closed);
+	}
 
-		if json.method != T::NOTIFICATION || json.params.subscription != subscription_id {
-			return Poll::Pending;
+	#[test]
+	fn pending_subscriptions_keep_the_connection_open() {
+		let provider = WebSocketProvider::new("http://127.0.0.1:8899");
+		let handles = provider.connect();
+
+		// what `create_subscription` stores once the response is received
+		{
+			let mut pending = provider.pending.lock().unwrap();
+			pending.ids.extend([1000, 1001]);
+			pending.handles = Some(handles.clone());
 		}
+		drop(handles);
+		check!(provider.connection_state() == ConnectionState::Connecting);
+
+		let first = Subscription::<SlotInfo>::new(&provider, 1000, 1);
+		drop(first);
+		check!(provider.connection_state() == ConnectionState::Connecting);
 
-		Poll::Ready(Some(json))
+		let second = Subscription::<SlotInfo>::new(&provider, 1001, 2);
+		check!(provider.pending.lock().unwrap().handles.is_none());
+		drop(second);
+		check!(provider.connection_state() == ConnectionState::Closed);
 	}
 }
 
@@ -418,9 +663,13 @@ mod websocket_provider_reqwest {
 		fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
 			let mut this = self.project();
 
+			if *this.ended {
+				return Poll::Ready(None);
+			}
+
 			if let Some(websocket) = this.websocket.as_mut().as_pin_mut() {
 				let Some(next) = ready!(websocket.poll_next(cx)) else {
-					this.ended = &mut true;
+					*this.ended = true;
 					return Poll::Ready(None);
 				};
 
@@ -431,7 +680,7 @@ mod websocket_provider_reqwest {
 			let result = ready!(initiator.poll(cx));
 
 			let Ok(websocket) = result else {
-				this.ended = &mut true;
+				*this.ended = true;
 				return Poll::Ready(None);
 			};
 
@@ -447,6 +696,10 @@ mod websocket_provider_reqwest {
 		fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
 			let mut this = self.project();
 
+			if *this.ended {
+				return Poll::Ready(Err(ClientWebSocketError::ConnectionClose));
+			}
+
 			if let Some(mut websocket) = this.websocket.as_mut().as_pin_mut() {
 				return websocket.poll_ready_unpin(cx).map_err(Into::into);
 			}
@@ -460,6 +713,7 @@ mod websocket_provider_reqwest {
 				return poll_result;
 			}
 
+			*this.ended = true;
 			Poll::Ready(Err(ClientWebSocketError::ConnectionError))
 		}
 
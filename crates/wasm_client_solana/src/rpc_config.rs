@@ -730,3 +730,157 @@ impl<'de> Deserialize<'de> for LogsSubscribeRequest {
 		})
 	}
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, TypedBuilder)]
+pub struct SignatureSubscribeRequest {
+	pub signature: Signature,
+	#[builder(default, setter(into, strip_option(fallback = config_opt)))]
+	pub config: Option<RpcSignatureSubscribeConfig>,
+}
+
+impl_websocket_method!(SignatureSubscribeRequest, "signature");
+
+impl From<Signature> for SignatureSubscribeRequest {
+	fn from(signature: Signature) -> Self {
+		Self::builder().signature(signature).build()
+	}
+}
+
+impl From<&Signature> for SignatureSubscribeRequest {
+	fn from(signature: &Signature) -> Self {
+		Self::builder().signature(*signature).build()
+	}
+}
+
+impl Serialize for SignatureSubscribeRequest {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		#[serde_as]
+		#[skip_serializing_none]
+		#[derive(Serialize)]
+		#[serde(rename = "SignatureSubscribeRequest")]
+		struct Inner<'a>(
+			#[serde_as(as = "DisplayFromStr")] &'a Signature,
+			&'a Option<RpcSignatureSubscribeConfig>,
+		);
+
+		let inner = Inner(&self.signature, &self.config);
+		Serialize::serialize(&inner, serde_tuple::Serializer(serializer))
+	}
+}
+
+impl<'de> Deserialize<'de> for SignatureSubscribeRequest {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		#[serde_as]
+		#[skip_serializing_none]
+		#[derive(Deserialize)]
+		#[serde(rename = "SignatureSubscribeRequest")]
+		struct Inner(
+			#[serde_as(as = "DisplayFromStr")] Signature,
+			Option<RpcSignatureSubscribeConfig>,
+		);
+
+		let inner: Inner = Deserialize::deserialize(serde_tuple::Deserializer(deserializer))?;
+		Ok(SignatureSubscribeRequest {
+			signature: inner.0,
+			config: inner.1,
+		})
+	}
+}
+
+/// Request for the `slotSubscribe` websocket method which takes no
+/// parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotSubscribeRequest;
+
+impl_websocket_method!(SlotSubscribeRequest, "slot");
+
+/// Request for the `rootSubscribe` websocket method which takes no
+/// parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootSubscribeRequest;
+
+impl_websocket_method!(RootSubscribeRequest, "root");
+
+/// Request for the `slotsUpdatesSubscribe` websocket method which takes no
+/// parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotsUpdatesSubscribeRequest;
+
+impl_websocket_method!(SlotsUpdatesSubscribeRequest, "slotsUpdates");
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use super::*;
+	use crate::ClientRequest;
+	use crate::methods::WebSocketMethod;
+
+	#[test]
+	fn signature_subscribe_request() {
+		let signature = Signature::from_str(
+			"2EBVM6cB8vAAD93Ktr6Vd8p67XPbQzCJX47MpReuiCXJAtcjaxpvWpcg9Ege1Nr5Tk3a2GFrByT7WPBjdsTycY9b",
+		)
+		.unwrap();
+		let request = ClientRequest::builder()
+			.method(SignatureSubscribeRequest::SUBSCRIBE)
+			.id(1)
+			.params(
+				SignatureSubscribeRequest::builder()
+					.signature(signature)
+					.config(
+						RpcSignatureSubscribeConfig::builder()
+							.commitment(CommitmentConfig::finalized())
+							.enable_received_notification(false)
+							.build(),
+					)
+					.build(),
+			)
+			.build();
+
+		insta::assert_compact_json_snapshot!(request, @r###"
+  {
+    "jsonrpc": "2.0",
+    "id": 1,
+    "method": "signatureSubscribe",
+    "params": [
+      "2EBVM6cB8vAAD93Ktr6Vd8p67XPbQzCJX47MpReuiCXJAtcjaxpvWpcg9Ege1Nr5Tk3a2GFrByT7WPBjdsTycY9b",
+      {
+        "commitment": "finalized",
+        "enableReceivedNotification": false
+      }
+    ]
+  }
+  "###);
+	}
+
+	#[test]
+	fn slot_subscribe_requests() {
+		let request = ClientRequest::builder()
+			.method(SlotSubscribeRequest::SUBSCRIBE)
+			.id(1)
+			.params(SlotSubscribeRequest)
+			.build();
+		insta::assert_compact_json_snapshot!(request, @r###"{"jsonrpc": "2.0", "id": 1, "method": "slotSubscribe"}"###);
+
+		let request = ClientRequest::builder()
+			.method(RootSubscribeRequest::SUBSCRIBE)
+			.id(2)
+			.params(RootSubscribeRequest)
+			.build();
+		insta::assert_compact_json_snapshot!(request, @r###"{"jsonrpc": "2.0", "id": 2, "method": "rootSubscribe"}"###);
+
+		let request = ClientRequest::builder()
+			.method(SlotsUpdatesSubscribeRequest::SUBSCRIBE)
+			.id(3)
+			.params(SlotsUpdatesSubscribeRequest)
+			.build();
+		insta::assert_compact_json_snapshot!(request, @r###"{"jsonrpc": "2.0", "id": 3, "method": "slotsUpdatesSubscribe"}"###);
+	}
+}
@@ -249,6 +249,13 @@ pub struct SlotInfo {
 	pub root: Slot,
 }
 
+/// The slot number received from a `rootNotification`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct RootNotificationResponse(pub Slot);
+
+impl_websocket_notification!(RootNotificationResponse, "root");
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct SlotTransactionStats {
@@ -294,6 +301,10 @@ pub enum SlotUpdate {
 	},
 }
 
+impl_websocket_notification!(SlotInfo, "slot");
+
+impl_websocket_notification!(SlotUpdate, "slotsUpdates");
+
 impl SlotUpdate {
 	pub fn slot(&self) -> Slot {
 		match self {
@@ -347,6 +358,14 @@ pub enum ReceivedSignatureResult {
 	ReceivedSignature,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SignatureNotificationResponse {
+	pub context: Context,
+	pub value: RpcSignatureResult,
+}
+
+impl_websocket_notification!(SignatureNotificationResponse, "signature");
+
 #[serde_as]
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -701,3 +720,88 @@ pub struct RpcPrioritizationFee {
 	pub slot: Slot,
 	pub prioritization_fee: u64,
 }
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+
+	use super::*;
+	use crate::SubscriptionResponse;
+
+	#[test]
+	fn signature_notification() {
+		let raw_json = r#"{"jsonrpc":"2.0","method":"signatureNotification","params":{"result":{"context":{"slot":5207624},"value":{"err":null}},"subscription":24006}}"#;
+
+		let response: SubscriptionResponse<SignatureNotificationResponse> =
+			serde_json::from_str(raw_json).unwrap();
+
+		check!(response.method == "signatureNotification");
+		check!(response.params.subscription == 24006);
+		check!(response.params.result.context.slot == 5_207_624);
+		check!(
+			response.params.result.value
+				== RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err: None })
+		);
+	}
+
+	#[test]
+	fn received_signature_notification() {
+		let raw_json = r#"{"jsonrpc":"2.0","method":"signatureNotification","params":{"result":{"context":{"slot":5207624},"value":"receivedSignature"},"subscription":24006}}"#;
+
+		let response: SubscriptionResponse<SignatureNotificationResponse> =
+			serde_json::from_str(raw_json).unwrap();
+
+		check!(
+			response.params.result.value
+				== RpcSignatureResult::ReceivedSignature(
+					ReceivedSignatureResult::ReceivedSignature
+				)
+		);
+	}
+
+	#[test]
+	fn slot_notification() {
+		let raw_json = r#"{"jsonrpc":"2.0","method":"slotNotification","params":{"result":{"parent":75,"root":44,"slot":76},"subscription":0}}"#;
+
+		let response: SubscriptionResponse<SlotInfo> = serde_json::from_str(raw_json).unwrap();
+
+		check!(response.method == "slotNotification");
+		check!(
+			response.params.result
+				== SlotInfo {
+					slot: 76,
+					parent: 75,
+					root: 44,
+				}
+		);
+	}
+
+	#[test]
+	fn root_notification() {
+		let raw_json = r#"{"jsonrpc":"2.0","method":"rootNotification","params":{"result":42,"subscription":0}}"#;
+
+		let response: SubscriptionResponse<RootNotificationResponse> =
+			serde_json::from_str(raw_json).unwrap();
+
+		check!(response.method == "rootNotification");
+		check!(response.params.result == RootNotificationResponse(42));
+	}
+
+	#[test]
+	fn slots_updates_notification() {
+		let raw_json = r#"{"jsonrpc":"2.0","method":"slotsUpdatesNotification","params":{"result":{"parent":75,"slot":76,"timestamp":1625081266243,"type":"createdBank"},"subscription":0}}"#;
+
+		let response: SubscriptionResponse<SlotUpdate> = serde_json::from_str(raw_json).unwrap();
+
+		check!(response.method == "slotsUpdatesNotification");
+		check!(
+			response.params.result
+				== SlotUpdate::CreatedBank {
+					slot: 76,
+					parent: 75,
+					timestamp: 1_625_081_266_243,
+				}
+		);
+		check!(response.params.result.slot() == 76);
+	}
+}
@@ -0,0 +1,510 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use solana_sdk::account::Account;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::SimulateTransactionResponseValue;
+use crate::SolanaRpcClient;
+use crate::rpc_config::RpcAccountInfoConfig;
+use crate::rpc_config::RpcSimulateTransactionConfig;
+
+/// The outcome of a single program invocation parsed from the simulation
+/// logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvocationStatus {
+	Success,
+	/// The program failed with the provided message.
+	Failed(String),
+	/// The logs ended before the program finished, e.g. because they were
+	/// truncated.
+	Incomplete,
+}
+
+/// A program invocation and the invocations it made through cross program
+/// invocations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramInvocation {
+	pub program_id: Pubkey,
+	/// The stack height of the invocation, starting at `1` for the top level
+	/// instructions.
+	pub depth: usize,
+	/// The compute units consumed by this invocation, including the units of
+	/// the inner invocations.
+	pub compute_units_consumed: Option<u64>,
+	pub status: InvocationStatus,
+	/// The `Program log:` messages emitted directly by this invocation.
+	pub logs: Vec<String>,
+	pub inner: Vec<ProgramInvocation>,
+}
+
+impl ProgramInvocation {
+	fn new(program_id: Pubkey, depth: usize) -> Self {
+		Self {
+			program_id,
+			depth,
+			compute_units_consumed: None,
+			status: InvocationStatus::Incomplete,
+			logs: vec![],
+			inner: vec![],
+		}
+	}
+
+	/// The compute units consumed by this invocation excluding the units of
+	/// its inner invocations.
+	pub fn own_compute_units(&self) -> u64 {
+		let inner = self
+			.inner
+			.iter()
+			.filter_map(|invocation| invocation.compute_units_consumed)
+			.sum::<u64>();
+
+		self.compute_units_consumed
+			.unwrap_or_default()
+			.saturating_sub(inner)
+	}
+}
+
+/// The instruction which caused the transaction to fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedInstruction {
+	/// The index of the instruction in the transaction.
+	pub index: u8,
+	/// The program invoked by the instruction.
+	pub program_id: Option<Pubkey>,
+	pub error: InstructionError,
+}
+
+/// The lamports of an account before and after the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountBalance {
+	pub pubkey: Pubkey,
+	/// `None` when the account didn't exist before the simulation.
+	pub pre: Option<u64>,
+	/// `None` when the account doesn't exist after the simulation.
+	pub post: Option<u64>,
+}
+
+impl AccountBalance {
+	/// The change in lamports caused by the simulated transaction.
+	pub fn change(&self) -> i128 {
+		i128::from(self.post.unwrap_or_default()) - i128::from(self.pre.unwrap_or_default())
+	}
+}
+
+/// A structured view of a transaction simulation created with
+/// [`SolanaRpcClient::simulate_transaction_with_analysis`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationAnalysis {
+	pub err: Option<TransactionError>,
+	/// The instruction which failed when `err` is an
+	/// [`TransactionError::InstructionError`].
+	pub failed_instruction: Option<FailedInstruction>,
+	pub units_consumed: Option<u64>,
+	/// The top level program invocations, one per instruction which ran.
+	pub invocations: Vec<ProgramInvocation>,
+	/// The balances of the accounts requested with the `accounts` config.
+	pub balances: Vec<AccountBalance>,
+	pub logs: Vec<String>,
+}
+
+impl SimulationAnalysis {
+	/// Analyze the simulation `result` of the `transaction`. The
+	/// `pre_accounts` are the accounts requested with the `accounts` config
+	/// before the simulation, in the same order as the `addresses`.
+	pub fn new(
+		transaction: &VersionedTransaction,
+		result: SimulateTransactionResponseValue,
+		addresses: &[Pubkey],
+		pre_accounts: &[Option<Account>],
+	) -> Self {
+		let failed_instruction = match &result.err {
+			Some(TransactionError::InstructionError(index, error)) => {
+				let message = &transaction.message;
+				let program_id = message
+					.instructions()
+					.get(usize::from(*index))
+					.and_then(|instruction| {
+						message
+							.static_account_keys()
+							.get(usize::from(instruction.program_id_index))
+					})
+					.copied();
+
+				Some(FailedInstruction {
+					index: *index,
+					program_id,
+					error: error.clone(),
+				})
+			}
+			_ => None,
+		};
+		let post_accounts = result.accounts.unwrap_or_default();
+		let balances = addresses
+			.iter()
+			.enumerate()
+			.map(|(index, pubkey)| {
+				AccountBalance {
+					pubkey: *pubkey,
+					pre: pre_accounts
+						.get(index)
+						.and_then(Option::as_ref)
+						.map(|account| account.lamports),
+					post: post_accounts
+						.get(index)
+						.and_then(Option::as_ref)
+						.map(|account| account.lamports),
+				}
+			})
+			.collect();
+		let logs = result.logs.unwrap_or_default();
+
+		Self {
+			err: result.err,
+			failed_instruction,
+			units_consumed: result.units_consumed,
+			invocations: parse_invocations(&logs),
+			balances,
+			logs,
+		}
+	}
+
+	/// The compute units consumed by each program, excluding the units of the
+	/// programs it invoked.
+	pub fn compute_units_by_program(&self) -> HashMap<Pubkey, u64> {
+		fn collect(invocations: &[ProgramInvocation], units: &mut HashMap<Pubkey, u64>) {
+			for invocation in invocations {
+				*units.entry(invocation.program_id).or_default() += invocation.own_compute_units();
+				collect(&invocation.inner, units);
+			}
+		}
+
+		let mut units = HashMap::new();
+		collect(&self.invocations, &mut units);
+
+		units
+	}
+}
+
+/// Parse the program invocation tree from the simulation `logs`.
+pub fn parse_invocations(logs: &[String]) -> Vec<ProgramInvocation> {
+	let mut roots = vec![];
+	let mut stack: Vec<ProgramInvocation> = vec![];
+
+	for log in logs {
+		if let Some(message) = log.strip_prefix("Program log: ") {
+			if let Some(invocation) = stack.last_mut() {
+				invocation.logs.push(message.to_string());
+			}
+
+			continue;
+		}
+
+		let Some(rest) = log.strip_prefix("Program ") else {
+			continue;
+		};
+		let Some((program_id, event)) = rest.split_once(' ') else {
+			continue;
+		};
+		let Ok(program_id) = Pubkey::from_str(program_id) else {
+			continue;
+		};
+
+		if let Some(depth) = event
+			.strip_prefix("invoke [")
+			.and_then(|depth| depth.strip_suffix(']'))
+			.and_then(|depth| depth.parse().ok())
+		{
+			stack.push(ProgramInvocation::new(program_id, depth));
+		} else if let Some(consumed) = event
+			.strip_prefix("consumed ")
+			.and_then(|consumed| consumed.split_once(' '))
+			.and_then(|(consumed, _)| consumed.parse().ok())
+		{
+			if let Some(invocation) = stack.last_mut() {
+				invocation.compute_units_consumed = Some(consumed);
+			}
+		} else if event == "success" || event.starts_with("failed: ") {
+			let Some(mut invocation) = stack.pop() else {
+				continue;
+			};
+
+			invocation.status = match event.strip_prefix("failed: ") {
+				Some(message) => InvocationStatus::Failed(message.to_string()),
+				None => InvocationStatus::Success,
+			};
+			push_invocation(&mut roots, &mut stack, invocation);
+		}
+	}
+
+	// invocations which never finished, innermost first
+	while let Some(invocation) = stack.pop() {
+		push_invocation(&mut roots, &mut stack, invocation);
+	}
+
+	roots
+}
+
+fn push_invocation(
+	roots: &mut Vec<ProgramInvocation>,
+	stack: &mut [ProgramInvocation],
+	invocation: ProgramInvocation,
+) {
+	match stack.last_mut() {
+		Some(parent) => parent.inner.push(invocation),
+		None => roots.push(invocation),
+	}
+}
+
+impl SolanaRpcClient {
+	/// Simulate the `transaction` and analyze the result. The balances of the
+	/// accounts requested with the `accounts` config are read before the
+	/// simulation so that their change can be reported.
+	///
+	/// Fails without sending a request when one of the `accounts` addresses
+	/// isn't a valid pubkey, since the balances are matched to the simulated
+	/// accounts by their position.
+	pub async fn simulate_transaction_with_analysis(
+		&self,
+		transaction: &VersionedTransaction,
+		config: RpcSimulateTransactionConfig,
+	) -> ClientResult<SimulationAnalysis> {
+		let addresses = config
+			.accounts
+			.iter()
+			.flat_map(|accounts| &accounts.addresses)
+			.map(|address| {
+				Pubkey::from_str(address).map_err(|error| {
+					ClientError::Other(format!("Invalid account address {address}: {error}"))
+				})
+			})
+			.collect::<ClientResult<Vec<_>>>()?;
+		// fetch the accounts at the same commitment as the simulation so the
+		// balance changes aren't computed against a different bank
+		let pre_accounts = if addresses.is_empty() {
+			vec![]
+		} else {
+			let account_config = RpcAccountInfoConfig {
+				commitment: Some(
+					config
+						.commitment
+						.unwrap_or_else(|| self.commitment_config()),
+				),
+				min_context_slot: config.min_context_slot,
+				..RpcAccountInfoConfig::default()
+			};
+
+			self.get_multiple_accounts_with_config(&addresses, account_config)
+				.await?
+		};
+		let result = self
+			.simulate_transaction_with_config(transaction, config)
+			.await?
+			.value;
+
+		Ok(SimulationAnalysis::new(
+			transaction,
+			result,
+			&addresses,
+			&pre_accounts,
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use assert2::check;
+	use serde_json::json;
+	use solana_sdk::commitment_config::CommitmentConfig;
+	use solana_sdk::hash::Hash;
+	use solana_sdk::system_instruction::transfer;
+	use solana_sdk::system_program;
+
+	use super::*;
+	use crate::VersionedTransactionExtension;
+	use crate::rpc_config::RpcSimulateTransactionAccountsConfig;
+	use crate::solana_account_decoder::UiAccountEncoding;
+	use crate::test_utils::TestProvider;
+	use crate::test_utils::account_json;
+
+	fn logs(program: &Pubkey, token: &Pubkey) -> Vec<String> {
+		[
+			format!("Program {program} invoke [1]"),
+			"Program log: Instruction: Swap".to_string(),
+			format!("Program {token} invoke [2]"),
+			"Program log: Instruction: Transfer".to_string(),
+			format!("Program {token} consumed 4645 of 190000 compute units"),
+			format!("Program {token} success"),
+			format!("Program {program} consumed 15000 of 200000 compute units"),
+			format!("Program {program} failed: custom program error: 0x1"),
+		]
+		.into()
+	}
+
+	#[test]
+	fn parse_invocation_tree() {
+		let program = Pubkey::new_unique();
+		let token = Pubkey::new_unique();
+		let invocations = parse_invocations(&logs(&program, &token));
+
+		check!(invocations.len() == 1);
+
+		let invocation = &invocations[0];
+		check!(invocation.program_id == program);
+		check!(invocation.depth == 1);
+		check!(invocation.compute_units_consumed == Some(15000));
+		check!(invocation.own_compute_units() == 10355);
+		check!(invocation.logs == vec!["Instruction: Swap".to_string()]);
+		check!(invocation.status == InvocationStatus::Failed("custom program error: 0x1".into()));
+		check!(invocation.inner.len() == 1);
+		check!(invocation.inner[0].program_id == token);
+		check!(invocation.inner[0].status == InvocationStatus::Success);
+	}
+
+	#[test]
+	fn incomplete_logs() {
+		let program = Pubkey::new_unique();
+		let token = Pubkey::new_unique();
+		let logs = vec![
+			format!("Program {program} invoke [1]"),
+			format!("Program {token} invoke [2]"),
+			"Log truncated".to_string(),
+		];
+		let invocations = parse_invocations(&logs);
+
+		check!(invocations.len() == 1);
+		check!(invocations[0].status == InvocationStatus::Incomplete);
+		check!(invocations[0].inner[0].status == InvocationStatus::Incomplete);
+	}
+
+	#[test]
+	fn analysis() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let recipient = Pubkey::new_unique();
+		let program = Pubkey::new_unique();
+		let token = Pubkey::new_unique();
+		let transaction = VersionedTransaction::new_unsigned_v0(
+			&payer,
+			&[transfer(&payer, &recipient, 1)],
+			&[],
+			Hash::default(),
+		)?;
+		let error = InstructionError::Custom(1);
+		let result = SimulateTransactionResponseValue {
+			err: Some(TransactionError::InstructionError(0, error.clone())),
+			logs: Some(logs(&program, &token)),
+			accounts: None,
+			units_consumed: Some(15000),
+			return_data: None,
+		};
+		let pre_account = Account {
+			lamports: 10,
+			..Account::default()
+		};
+		let analysis =
+			SimulationAnalysis::new(&transaction, result, &[recipient], &[Some(pre_account)]);
+
+		check!(
+			analysis.failed_instruction
+				== Some(FailedInstruction {
+					index: 0,
+					program_id: Some(system_program::id()),
+					error,
+				})
+		);
+		check!(
+			analysis.balances
+				== vec![AccountBalance {
+					pubkey: recipient,
+					pre: Some(10),
+					post: None,
+				}]
+		);
+		check!(analysis.balances[0].change() == -10);
+		check!(
+			analysis.compute_units_by_program() == HashMap::from([(program, 10355), (token, 4645)])
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn fetches_pre_accounts_at_the_simulation_commitment() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let recipient = Pubkey::new_unique();
+		let provider = Arc::new(TestProvider::new(|method, _| {
+			let value = if method == "getMultipleAccounts" {
+				json!([account_json(10)])
+			} else {
+				json!({ "err": null, "logs": [], "accounts": [null] })
+			};
+
+			Ok(json!({ "context": { "slot": 5 }, "value": value }))
+		}));
+		let transaction = VersionedTransaction::new_unsigned_v0(
+			&payer,
+			&[transfer(&payer, &recipient, 1)],
+			&[],
+			Hash::default(),
+		)?;
+		let config = RpcSimulateTransactionConfig {
+			commitment: Some(CommitmentConfig::processed()),
+			accounts: Some(RpcSimulateTransactionAccountsConfig {
+				encoding: Some(UiAccountEncoding::Base64),
+				addresses: vec![recipient.to_string()],
+			}),
+			min_context_slot: Some(5),
+			..RpcSimulateTransactionConfig::default()
+		};
+		let analysis = futures::executor::block_on(
+			provider
+				.rpc()
+				.simulate_transaction_with_analysis(&transaction, config),
+		)?;
+
+		let requests = provider.requests("getMultipleAccounts");
+		check!(requests.len() == 1);
+		check!(requests[0][1]["commitment"] == "processed");
+		check!(requests[0][1]["minContextSlot"] == 5);
+		check!(analysis.balances[0].pre == Some(10));
+
+		Ok(())
+	}
+
+	#[test]
+	fn rejects_invalid_account_addresses() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let recipient = Pubkey::new_unique();
+		let provider = Arc::new(TestProvider::new(|_, _| Ok(json!(null))));
+		let transaction = VersionedTransaction::new_unsigned_v0(
+			&payer,
+			&[transfer(&payer, &recipient, 1)],
+			&[],
+			Hash::default(),
+		)?;
+		let config = RpcSimulateTransactionConfig {
+			accounts: Some(RpcSimulateTransactionAccountsConfig {
+				encoding: Some(UiAccountEncoding::Base64),
+				addresses: vec![payer.to_string(), "invalid".into(), recipient.to_string()],
+			}),
+			..RpcSimulateTransactionConfig::default()
+		};
+		let result = futures::executor::block_on(
+			provider
+				.rpc()
+				.simulate_transaction_with_analysis(&transaction, config),
+		);
+
+		check!(let Err(ClientError::Other(_)) = result);
+		check!(provider.calls() == 0);
+
+		Ok(())
+	}
+}
@@ -1,8 +1,12 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
 use futures_timer::Delay;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 use solana_sdk::account::Account;
 use solana_sdk::clock::Epoch;
 use solana_sdk::clock::Slot;
@@ -21,17 +25,23 @@ use crate::ClientError;
 use crate::ClientResponse;
 use crate::ClientResult;
 use crate::HttpProvider;
+use crate::MAX_CONCURRENT_MULTIPLE_ACCOUNTS_REQUESTS;
+use crate::MAX_MULTIPLE_ACCOUNTS;
 use crate::MAX_RETRIES;
+use crate::RpcBatch;
 use crate::RpcError;
 use crate::RpcProvider;
 use crate::SLEEP_MS;
 use crate::Subscription;
+use crate::TransactionConfirmation;
 use crate::WebSocketProvider;
 use crate::methods::*;
+use crate::metrics;
 use crate::rpc_config::BlockSubscribeRequest;
 use crate::rpc_config::GetConfirmedSignaturesForAddress2Config;
 use crate::rpc_config::LogsSubscribeRequest;
 use crate::rpc_config::ProgramSubscribeRequest;
+use crate::rpc_config::RootSubscribeRequest;
 use crate::rpc_config::RpcAccountInfoConfig;
 use crate::rpc_config::RpcBlockConfig;
 use crate::rpc_config::RpcBlockProductionConfig;
@@ -48,9 +58,13 @@ use crate::rpc_config::RpcSimulateTransactionConfig;
 use crate::rpc_config::RpcSupplyConfig;
 use crate::rpc_config::RpcTokenAccountsFilter;
 use crate::rpc_config::RpcTransactionConfig;
+use crate::rpc_config::SignatureSubscribeRequest;
+use crate::rpc_config::SlotSubscribeRequest;
+use crate::rpc_config::SlotsUpdatesSubscribeRequest;
 use crate::rpc_filter::TokenAccountsFilter;
 use crate::rpc_response::BlockNotificationResponse;
 use crate::rpc_response::LogsNotificationResponse;
+use crate::rpc_response::RootNotificationResponse;
 use crate::rpc_response::RpcAccountBalance;
 use crate::rpc_response::RpcBlockProduction;
 use crate::rpc_response::RpcConfirmedTransactionStatusWithSignature;
@@ -63,8 +77,13 @@ use crate::rpc_response::RpcPrioritizationFee;
 use crate::rpc_response::RpcSupply;
 use crate::rpc_response::RpcVersionInfo;
 use crate::rpc_response::RpcVoteAccountStatus;
+use crate::rpc_response::SignatureNotificationResponse;
+use crate::rpc_response::SlotInfo;
+use crate::rpc_response::SlotUpdate;
+use crate::solana_account_decoder::UiAccount;
 use crate::solana_account_decoder::UiAccountData;
 use crate::solana_account_decoder::UiAccountEncoding;
+use crate::solana_account_decoder::UiDataSliceConfig;
 use crate::solana_account_decoder::parse_address_lookup_table::LookupTableAccountType;
 use crate::solana_account_decoder::parse_address_lookup_table::parse_address_lookup_table;
 use crate::solana_account_decoder::parse_token::TokenAccountType;
@@ -126,7 +145,8 @@ use crate::solana_transaction_status::UiTransactionEncoding;
 /// [`ClientResult`], and many of them
 /// return [`ClientResponse`].
 ///
-/// Requests may timeout, in which case they return a [`ClientError`].
+/// Requests may timeout, in which case they return a
+/// [`ClientError::Timeout`].
 #[derive(derive_more::Debug, Clone)]
 pub struct SolanaRpcClient {
 	commitment_config: CommitmentConfig,
@@ -209,11 +229,54 @@ impl SolanaRpcClient {
 		}
 	}
 
+	/// Create a new rpc from a configured [`HttpProvider`], e.g. one which
+	/// sends an api key header to a paid rpc provider.
+	///
+	/// ```rust
+	/// use solana_sdk::commitment_config::CommitmentConfig;
+	/// use wasm_client_solana::DEVNET;
+	/// use wasm_client_solana::HttpProvider;
+	/// use wasm_client_solana::SolanaRpcClient;
+	///
+	/// let provider = HttpProvider::builder()
+	/// 	.url(DEVNET)
+	/// 	.headers([("x-api-key", "<API_KEY>")])
+	/// 	.build();
+	/// let rpc = SolanaRpcClient::new_with_http_provider(provider, CommitmentConfig::confirmed());
+	/// ```
+	pub fn new_with_http_provider(
+		provider: HttpProvider,
+		commitment_config: CommitmentConfig,
+	) -> Self {
+		Self::new_with_provider(Arc::new(provider), commitment_config)
+	}
+
+	/// Create a new rpc from a custom provider and a custom
+	/// [`WebSocketProvider`], e.g. one created with
+	/// [`WebSocketProvider::new_with_config`] to reconnect dropped websocket
+	/// connections.
+	pub fn new_with_providers(
+		provider: Arc<dyn RpcProvider + Send + Sync + 'static>,
+		ws: WebSocketProvider,
+		commitment_config: CommitmentConfig,
+	) -> Self {
+		Self {
+			commitment_config,
+			provider,
+			ws,
+		}
+	}
+
 	/// Get the URL.
 	pub fn url(&self) -> String {
 		self.provider.url()
 	}
 
+	/// The [`WebSocketProvider`] used for subscriptions.
+	pub fn ws(&self) -> &WebSocketProvider {
+		&self.ws
+	}
+
 	pub fn commitment(&self) -> CommitmentLevel {
 		self.commitment_config.commitment
 	}
@@ -222,25 +285,102 @@ impl SolanaRpcClient {
 		self.commitment_config
 	}
 
-	async fn send<T: HttpMethod, R: DeserializeOwned>(&self, request: T) -> ClientResult<R> {
-		let result = self
-			.provider
-			.send(
-				T::NAME,
-				serde_json::to_value(request)
-					.map_err(|error| ClientError::Other(error.to_string()))?,
-			)
-			.await?;
+	/// Create an [`RpcBatch`] which sends multiple requests as a single
+	/// JSON-RPC batch request.
+	///
+	/// ```rust
+	/// # use solana_sdk::pubkey;
+	/// # use wasm_client_solana::ClientResponse;
+	/// # use wasm_client_solana::DEVNET;
+	/// # use wasm_client_solana::GetBalanceRequest;
+	/// # use wasm_client_solana::GetBalanceResponse;
+	/// # use wasm_client_solana::GetSlotRequest;
+	/// # use wasm_client_solana::GetSlotResponse;
+	/// # use wasm_client_solana::SolanaRpcClient;
+	///
+	/// # async fn run() -> anyhow::Result<()> {
+	/// let client = SolanaRpcClient::new(DEVNET);
+	/// let pubkey = pubkey!("99P8ZgtJYe1buSK8JXkvpLh8xPsCFuLYhz9hQFNw93WJ");
+	/// let mut batch = client.batch();
+	/// let balance = batch.add(GetBalanceRequest::new(pubkey))?;
+	/// let slot = batch.add(GetSlotRequest::default())?;
+	/// let responses = batch.send().await?;
+	///
+	/// let balance: ClientResponse<GetBalanceResponse> = responses.get(balance)?;
+	/// let slot: ClientResponse<GetSlotResponse> = responses.get(slot)?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn batch(&self) -> RpcBatch {
+		RpcBatch::new(self.provider.clone())
+	}
 
-		match serde_json::from_value::<R>(result.clone()) {
-			Ok(response) => Ok(response),
-			_ => {
-				match serde_json::from_value::<RpcError>(result) {
-					Ok(error) => Err(error.into()),
-					Err(error) => Err(ClientError::Other(error.to_string())),
-				}
-			}
-		}
+	/// Send any request which implements [`HttpMethod`] and deserialize the
+	/// response, usually into a [`ClientResponse`]. This can be used for rpc
+	/// methods which don't have a dedicated method on the client, e.g.
+	/// provider extensions.
+	///
+	/// ```
+	/// use serde::Deserialize;
+	/// use serde::Serialize;
+	/// use wasm_client_solana::ClientResponse;
+	/// use wasm_client_solana::HttpMethod;
+	/// use wasm_client_solana::SolanaRpcClient;
+	/// # use wasm_client_solana::ClientResult;
+	///
+	/// #[derive(Serialize)]
+	/// struct GetAssetRequest {
+	/// 	id: String,
+	/// }
+	///
+	/// impl HttpMethod for GetAssetRequest {
+	/// 	const NAME: &'static str = "getAsset";
+	/// }
+	///
+	/// #[derive(Deserialize)]
+	/// struct Asset {
+	/// 	id: String,
+	/// }
+	///
+	/// # async fn run(rpc: SolanaRpcClient) -> ClientResult<()> {
+	/// let request = GetAssetRequest {
+	/// 	id: "F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk".into(),
+	/// };
+	/// let response: ClientResponse<Asset> = rpc.send(request).await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn send<Req: HttpMethod, Res: DeserializeOwned>(
+		&self,
+		request: Req,
+	) -> ClientResult<Res> {
+		let request =
+			serde_json::to_value(request).map_err(|error| ClientError::Other(error.to_string()))?;
+		let result = metrics::instrument(Req::NAME, self.provider.send(Req::NAME, request)).await?;
+
+		parse_response(result)
+	}
+
+	/// Send the `method` with raw json `params` and return the `result` of the
+	/// response.
+	///
+	/// ```
+	/// use serde_json::json;
+	/// use wasm_client_solana::SolanaRpcClient;
+	/// # use wasm_client_solana::ClientResult;
+	///
+	/// # async fn run(rpc: SolanaRpcClient) -> ClientResult<()> {
+	/// let asset = rpc
+	/// 	.send_json("getAsset", json!({ "id": "F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk" }))
+	/// 	.await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn send_json(&self, method: &'static str, params: Value) -> ClientResult<Value> {
+		let result = metrics::instrument(method, self.provider.send(method, params)).await?;
+		let response: ClientResponse<Value> = parse_response(result)?;
+
+		Ok(response.result)
 	}
 
 	pub async fn get_account_with_config(
@@ -500,6 +640,68 @@ impl SolanaRpcClient {
 			.await
 	}
 
+	/// Wait for the transaction with the provided `signature` to reach the
+	/// commitment level of the `commitment_config`.
+	///
+	/// Unlike [`SolanaRpcClient::confirm_transaction_with_commitment`] this
+	/// keeps polling until the block height passes the
+	/// `last_valid_block_height` of the blockhash used by the transaction, as
+	/// returned by [`SolanaRpcClient::get_latest_blockhash_with_commitment`].
+	/// Once the block height has passed, the transaction can no longer be
+	/// processed and [`TransactionConfirmation::Expired`] is returned.
+	///
+	/// ```rust
+	/// # use solana_sdk::signature::Signature;
+	/// # use wasm_client_solana::ClientResult;
+	/// # use wasm_client_solana::SolanaRpcClient;
+	/// use wasm_client_solana::TransactionConfirmation;
+	///
+	/// # async fn run(rpc: SolanaRpcClient, signature: Signature, last_valid_block_height: u64) -> ClientResult<()> {
+	/// let confirmation = rpc
+	/// 	.confirm_transaction_with_block_height(
+	/// 		&signature,
+	/// 		last_valid_block_height,
+	/// 		rpc.commitment_config(),
+	/// 	)
+	/// 	.await?;
+	///
+	/// match confirmation {
+	/// 	TransactionConfirmation::Confirmed => println!("confirmed"),
+	/// 	TransactionConfirmation::Failed(error) => println!("failed: {error}"),
+	/// 	TransactionConfirmation::Expired => println!("expired, rebuild and resend"),
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn confirm_transaction_with_block_height(
+		&self,
+		signature: &Signature,
+		last_valid_block_height: u64,
+		commitment_config: CommitmentConfig,
+	) -> ClientResult<TransactionConfirmation> {
+		loop {
+			// the block height must be read before the status so that a
+			// transaction which lands before the blockhash expires is never
+			// reported as expired.
+			let block_height = self
+				.get_block_height_with_commitment(commitment_config)
+				.await?;
+			let signature_statuses = self.get_signature_statuses(&[*signature]).await?;
+			let status = signature_statuses.first().and_then(Option::as_ref);
+
+			if let Some(confirmation) = TransactionConfirmation::from_status(
+				status,
+				commitment_config,
+				block_height,
+				last_valid_block_height,
+			) {
+				return Ok(confirmation);
+			}
+
+			Delay::new(Duration::from_millis(SLEEP_MS)).await;
+		}
+	}
+
 	pub async fn send_and_confirm_transaction_with_config(
 		&self,
 		transaction: &VersionedTransaction,
@@ -597,6 +799,91 @@ impl SolanaRpcClient {
 		.await
 	}
 
+	/// Stream the accounts owned by the program in pages of `page_size`
+	/// accounts, so that large programs don't cause the request to time out.
+	///
+	/// The matching pubkeys are requested first with an empty `dataSlice` and
+	/// the accounts are then fetched with `getMultipleAccounts` for each page.
+	/// Accounts which are closed between the requests are skipped.
+	///
+	/// The stream fails without sending a request when `page_size` is `0`.
+	///
+	/// ```
+	/// use futures::TryStreamExt;
+	/// use solana_sdk::pubkey::Pubkey;
+	/// use wasm_client_solana::SolanaRpcClient;
+	/// use wasm_client_solana::rpc_config::RpcProgramAccountsConfig;
+	/// # use wasm_client_solana::ClientResult;
+	///
+	/// # async fn run(rpc: SolanaRpcClient, program_id: Pubkey) -> ClientResult<()> {
+	/// let mut pages = std::pin::pin!(rpc.get_program_accounts_pages(
+	/// 	&program_id,
+	/// 	RpcProgramAccountsConfig::default(),
+	/// 	100
+	/// ));
+	///
+	/// while let Some(accounts) = pages.try_next().await? {
+	/// 	log::info!("received {} accounts", accounts.len());
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn get_program_accounts_pages(
+		&self,
+		pubkey: &Pubkey,
+		config: RpcProgramAccountsConfig,
+		page_size: usize,
+	) -> impl Stream<Item = ClientResult<Vec<(Pubkey, Account)>>> + '_ {
+		let pubkey = *pubkey;
+		let account_config = config.account_config.clone();
+		let keys_config = RpcProgramAccountsConfig {
+			account_config: RpcAccountInfoConfig {
+				encoding: Some(UiAccountEncoding::Base64),
+				data_slice: Some(UiDataSliceConfig {
+					offset: 0,
+					length: 0,
+				}),
+				..config.account_config.clone()
+			},
+			..config
+		};
+
+		futures::stream::once(async move {
+			if page_size == 0 {
+				return Err(ClientError::Other(
+					"page_size must be greater than 0".into(),
+				));
+			}
+
+			self.get_program_accounts_with_config(&pubkey, keys_config)
+				.await
+		})
+		.map_ok(move |keyed_accounts| {
+			let pages = keyed_accounts
+				.chunks(page_size)
+				.map(|page| page.iter().map(|(pubkey, _)| *pubkey).collect::<Vec<_>>())
+				.collect::<Vec<_>>();
+
+			futures::stream::iter(pages).map(Ok)
+		})
+		.try_flatten()
+		.and_then(move |pubkeys| {
+			let account_config = account_config.clone();
+
+			async move {
+				let accounts = self
+					.get_multiple_accounts_with_config(&pubkeys, account_config)
+					.await?;
+
+				Ok(pubkeys
+					.into_iter()
+					.zip(accounts)
+					.filter_map(|(pubkey, account)| account.map(|account| (pubkey, account)))
+					.collect())
+			}
+		})
+	}
+
 	pub async fn get_slot_with_commitment(
 		&self,
 		commitment_config: CommitmentConfig,
@@ -829,26 +1116,41 @@ impl SolanaRpcClient {
 			.await
 	}
 
+	/// Get the accounts at the `pubkeys` in the same order. `None` is returned
+	/// for accounts which don't exist.
+	///
+	/// Requests for more than [`MAX_MULTIPLE_ACCOUNTS`] accounts are split into
+	/// chunks which are sent in parallel, with at most
+	/// [`MAX_CONCURRENT_MULTIPLE_ACCOUNTS_REQUESTS`] in flight.
 	pub async fn get_multiple_accounts_with_config(
 		&self,
 		pubkeys: &[Pubkey],
 		config: RpcAccountInfoConfig,
 	) -> ClientResult<Vec<Option<Account>>> {
-		let config = RpcAccountInfoConfig {
-			commitment: config.commitment,
-			..config
-		};
-
-		let request = GetMultipleAccountsRequest::new_with_config(pubkeys.to_vec(), config);
-		let response: ClientResponse<GetMultipleAccountsResponse> = self.send(request).await?;
+		let requests = pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS).map(|chunk| {
+			let request =
+				GetMultipleAccountsRequest::new_with_config(chunk.to_vec(), config.clone());
+
+			async move {
+				let response: ClientResponse<GetMultipleAccountsResponse> =
+					self.send(request).await?;
+
+				ClientResult::Ok(
+					response
+						.result
+						.value
+						.iter()
+						.map(|account| account.as_ref().and_then(UiAccount::decode))
+						.collect::<Vec<_>>(),
+				)
+			}
+		});
+		let chunks: Vec<Vec<Option<Account>>> = futures::stream::iter(requests)
+			.buffered(MAX_CONCURRENT_MULTIPLE_ACCOUNTS_REQUESTS)
+			.try_collect()
+			.await?;
 
-		Ok(response
-			.result
-			.value
-			.iter()
-			.filter(|maybe_acc| maybe_acc.is_some())
-			.map(|acc| acc.clone().unwrap().decode())
-			.collect())
+		Ok(chunks.concat())
 	}
 
 	pub async fn get_multiple_accounts_with_commitment(
@@ -1425,8 +1727,7 @@ impl SolanaRpcClient {
 		request: impl Into<GetAccountInfoRequest>,
 	) -> ClientResult<Subscription<GetAccountInfoResponse>> {
 		let request: GetAccountInfoRequest = request.into();
-		let (id, subscription_id) = self.ws.create_subscription(request).await?;
-		let subscription = Subscription::new(&self.ws, id, subscription_id);
+		let subscription = self.ws.subscribe(request).await?;
 
 		Ok(subscription)
 	}
@@ -1448,8 +1749,7 @@ impl SolanaRpcClient {
 		&self,
 		request: BlockSubscribeRequest,
 	) -> ClientResult<Subscription<BlockNotificationResponse>> {
-		let (id, subscription_id) = self.ws.create_subscription(request).await?;
-		let subscription = Subscription::new(&self.ws, id, subscription_id);
+		let subscription = self.ws.subscribe(request).await?;
 
 		Ok(subscription)
 	}
@@ -1468,8 +1768,7 @@ impl SolanaRpcClient {
 		&self,
 		request: LogsSubscribeRequest,
 	) -> ClientResult<Subscription<LogsNotificationResponse>> {
-		let (id, subscription_id) = self.ws.create_subscription(request).await?;
-		let subscription = Subscription::new(&self.ws, id, subscription_id);
+		let subscription = self.ws.subscribe(request).await?;
 
 		Ok(subscription)
 	}
@@ -1488,9 +1787,323 @@ impl SolanaRpcClient {
 		&self,
 		request: ProgramSubscribeRequest,
 	) -> ClientResult<Subscription<GetProgramAccountsResponse>> {
-		let (id, subscription_id) = self.ws.create_subscription(request).await?;
-		let subscription = Subscription::new(&self.ws, id, subscription_id);
+		let subscription = self.ws.subscribe(request).await?;
+
+		Ok(subscription)
+	}
+
+	/// Subscribe to signature events.
+	///
+	/// Receives a [`SignatureNotificationResponse`] once the transaction with
+	/// the given signature reaches the commitment level set in the
+	/// [`RpcSignatureSubscribeConfig`](crate::rpc_config::RpcSignatureSubscribeConfig).
+	/// When `enable_received_notification` is set, an additional notification
+	/// is sent as soon as the signature is received by the rpc node.
+	///
+	/// The server automatically cancels the subscription after the final
+	/// notification, so there is no need to call
+	/// [`Subscription::unsubscribe`] once it has resolved.
+	///
+	/// # RPC Reference
+	///
+	/// This method corresponds directly to the [`signatureSubscribe`] RPC
+	/// method.
+	///
+	/// [`signatureSubscribe`]: https://solana.com/docs/rpc/websocket/signaturesubscribe
+	///
+	/// ```rust
+	/// # use solana_sdk::commitment_config::CommitmentConfig;
+	/// # use solana_sdk::signature::Signature;
+	/// # use wasm_client_solana::DEVNET;
+	/// # use wasm_client_solana::SolanaRpcClient;
+	/// # use wasm_client_solana::rpc_config::RpcSignatureSubscribeConfig;
+	/// # use wasm_client_solana::rpc_config::SignatureSubscribeRequest;
+	/// # use wasm_client_solana::rpc_response::RpcSignatureResult;
+	/// # use futures::StreamExt;
+	///
+	/// # async fn run(signature: Signature) -> anyhow::Result<()> {
+	/// let client = SolanaRpcClient::new(DEVNET);
+	/// let request = SignatureSubscribeRequest::builder()
+	/// 	.signature(signature)
+	/// 	.config(
+	/// 		RpcSignatureSubscribeConfig::builder()
+	/// 			.commitment(CommitmentConfig::finalized())
+	/// 			.build(),
+	/// 	)
+	/// 	.build();
+	/// let mut subscription = client.signature_subscribe(request).await?;
+	///
+	/// if let Some(notification) = subscription.next().await {
+	/// 	if let RpcSignatureResult::ProcessedSignature(result) = notification.params.result.value {
+	/// 		println!("Finalized with error: {:?}", result.err);
+	/// 	}
+	/// }
+	///
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn signature_subscribe(
+		&self,
+		request: impl Into<SignatureSubscribeRequest>,
+	) -> ClientResult<Subscription<SignatureNotificationResponse>> {
+		let request: SignatureSubscribeRequest = request.into();
+		let subscription = self.ws.subscribe(request).await?;
+
+		Ok(subscription)
+	}
+
+	/// Subscribe to slot events.
+	///
+	/// Receives messages of type [`SlotInfo`] whenever a slot is processed by
+	/// the validator.
+	///
+	/// # RPC Reference
+	///
+	/// This method corresponds directly to the [`slotSubscribe`] RPC method.
+	///
+	/// [`slotSubscribe`]: https://solana.com/docs/rpc/websocket/slotsubscribe
+	///
+	/// ```rust
+	/// # use wasm_client_solana::DEVNET;
+	/// # use wasm_client_solana::SolanaRpcClient;
+	/// # use futures::StreamExt;
+	///
+	/// # async fn run() -> anyhow::Result<()> {
+	/// let client = SolanaRpcClient::new(DEVNET);
+	/// let mut subscription = client.slot_subscribe().await?;
+	///
+	/// while let Some(notification) = subscription.next().await {
+	/// 	println!("Slot: {}", notification.params.result.slot);
+	/// }
+	///
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub async fn slot_subscribe(&self) -> ClientResult<Subscription<SlotInfo>> {
+		let subscription = self.ws.subscribe(SlotSubscribeRequest).await?;
+
+		Ok(subscription)
+	}
+
+	/// Subscribe to root events.
+	///
+	/// Receives messages of type [`RootNotificationResponse`] whenever a new
+	/// root is set by the validator.
+	///
+	/// # RPC Reference
+	///
+	/// This method corresponds directly to the [`rootSubscribe`] RPC method.
+	///
+	/// [`rootSubscribe`]: https://solana.com/docs/rpc/websocket/rootsubscribe
+	pub async fn root_subscribe(&self) -> ClientResult<Subscription<RootNotificationResponse>> {
+		let subscription = self.ws.subscribe(RootSubscribeRequest).await?;
+
+		Ok(subscription)
+	}
+
+	/// Subscribe to slot update events.
+	///
+	/// Receives messages of type [`SlotUpdate`] for every stage a slot passes
+	/// through on the validator, from the first shred being received to the
+	/// slot becoming rooted.
+	///
+	/// This method is marked as unstable by the rpc and may not be available
+	/// on every node.
+	///
+	/// # RPC Reference
+	///
+	/// This method corresponds directly to the [`slotsUpdatesSubscribe`] RPC
+	/// method.
+	///
+	/// [`slotsUpdatesSubscribe`]: https://solana.com/docs/rpc/websocket/slotsupdatessubscribe
+	pub async fn slots_updates_subscribe(&self) -> ClientResult<Subscription<SlotUpdate>> {
+		let subscription = self.ws.subscribe(SlotsUpdatesSubscribeRequest).await?;
 
 		Ok(subscription)
 	}
 }
+
+/// Deserialize the response of an rpc request or the [`RpcError`] it contains.
+pub(crate) fn parse_response<R: DeserializeOwned>(result: Value) -> ClientResult<R> {
+	match serde_json::from_value::<R>(result.clone()) {
+		Ok(response) => Ok(response),
+		_ => {
+			match serde_json::from_value::<RpcError>(result) {
+				Ok(error) => Err(error.into()),
+				Err(error) => Err(ClientError::Other(error.to_string())),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use serde_json::json;
+
+	use super::*;
+	use crate::test_utils::TestProvider;
+	use crate::test_utils::account_json;
+	use crate::test_utils::rpc_error;
+
+	/// Responds with an account for every requested pubkey whose lamports are
+	/// its position in `pubkeys`. The pubkeys at the `missing` positions don't
+	/// exist.
+	fn accounts_provider(pubkeys: &[Pubkey], missing: &[usize]) -> Arc<TestProvider> {
+		let pubkeys = pubkeys.to_vec();
+		let missing = missing.to_vec();
+		let provider = TestProvider::new(move |method, request| {
+			let account = |pubkey: &Pubkey| {
+				match pubkeys.iter().position(|key| key == pubkey) {
+					Some(index) if !missing.contains(&index) => account_json(index as u64),
+					_ => Value::Null,
+				}
+			};
+
+			match method {
+				"getMultipleAccounts" => {
+					let accounts = request[0]
+						.as_array()
+						.unwrap()
+						.iter()
+						.map(|pubkey| account(&pubkey.as_str().unwrap().parse().unwrap()))
+						.collect::<Vec<_>>();
+
+					Ok(json!({ "context": { "slot": 1 }, "value": accounts }))
+				}
+				"getProgramAccounts" => {
+					let accounts = pubkeys
+						.iter()
+						.map(
+							|pubkey| json!({ "pubkey": pubkey.to_string(), "account": account_json(0) }),
+						)
+						.collect::<Vec<_>>();
+
+					Ok(json!(accounts))
+				}
+				_ => Err(rpc_error(-32601, "Method not found")),
+			}
+		});
+
+		Arc::new(provider)
+	}
+
+	fn unique_pubkeys(count: usize) -> Vec<Pubkey> {
+		(0..count).map(|_| Pubkey::new_unique()).collect()
+	}
+
+	#[test]
+	fn send_custom_requests() -> anyhow::Result<()> {
+		#[derive(serde::Serialize)]
+		struct CustomRequest(u8);
+
+		impl HttpMethod for CustomRequest {
+			const NAME: &'static str = "getProgramAccounts";
+		}
+
+		let provider = accounts_provider(&unique_pubkeys(2), &[]);
+		let rpc = provider.rpc();
+		let response: ClientResponse<Vec<RpcKeyedAccount>> =
+			futures::executor::block_on(rpc.send(CustomRequest(1)))?;
+
+		check!(response.result.len() == 2);
+		check!(provider.requests("getProgramAccounts") == vec![json!(1)]);
+
+		let result =
+			futures::executor::block_on(rpc.send_json("getMultipleAccounts", json!([[]])))?;
+
+		check!(result == json!({ "context": { "slot": 1 }, "value": [] }));
+
+		let error = futures::executor::block_on(rpc.send_json("unknownMethod", json!([])));
+
+		check!(let Err(ClientError::Rpc(_)) = error);
+
+		Ok(())
+	}
+
+	#[test]
+	fn get_multiple_accounts_in_chunks() -> anyhow::Result<()> {
+		let pubkeys = unique_pubkeys(250);
+		let provider = accounts_provider(&pubkeys, &[249]);
+		let rpc = provider.rpc();
+		let accounts = futures::executor::block_on(rpc.get_multiple_accounts(&pubkeys))?;
+		let lamports = accounts
+			.iter()
+			.map(|account| account.as_ref().map(|account| account.lamports))
+			.collect::<Vec<_>>();
+		let expected = (0..249).map(Some).chain([None]).collect::<Vec<_>>();
+
+		check!(lamports == expected);
+		check!(
+			provider
+				.requests("getMultipleAccounts")
+				.iter()
+				.map(|request| request[0].as_array().unwrap().len())
+				.collect::<Vec<_>>()
+				== vec![100, 100, 50]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn get_multiple_accounts_keeps_missing_accounts_in_position() -> anyhow::Result<()> {
+		let pubkeys = unique_pubkeys(150);
+		let missing = [50, 99, 100, 120];
+		let provider = accounts_provider(&pubkeys, &missing);
+		let rpc = provider.rpc();
+		let accounts = futures::executor::block_on(rpc.get_multiple_accounts(&pubkeys))?;
+		let lamports = accounts
+			.iter()
+			.map(|account| account.as_ref().map(|account| account.lamports))
+			.collect::<Vec<_>>();
+		let expected = (0..150)
+			.map(|index| (!missing.contains(&index)).then_some(index as u64))
+			.collect::<Vec<_>>();
+
+		check!(lamports == expected);
+		check!(provider.requests("getMultipleAccounts").len() == 2);
+
+		Ok(())
+	}
+
+	#[test]
+	fn get_program_accounts_in_pages() -> anyhow::Result<()> {
+		let provider = accounts_provider(&unique_pubkeys(25), &[24]);
+		let rpc = provider.rpc();
+		let pages = futures::executor::block_on(
+			rpc.get_program_accounts_pages(
+				&Pubkey::new_unique(),
+				RpcProgramAccountsConfig::default(),
+				10,
+			)
+			.try_collect::<Vec<_>>(),
+		)?;
+
+		check!(pages.iter().map(Vec::len).collect::<Vec<_>>() == vec![10, 10, 4]);
+		check!(pages.concat()[12].1.lamports == 12);
+
+		let program_requests = provider.requests("getProgramAccounts");
+		check!(program_requests.len() == 1);
+		check!(program_requests[0][1]["dataSlice"] == json!({ "offset": 0, "length": 0 }));
+
+		Ok(())
+	}
+
+	#[test]
+	fn get_program_accounts_pages_rejects_empty_pages() {
+		let provider = accounts_provider(&unique_pubkeys(5), &[]);
+		let rpc = provider.rpc();
+		let result = futures::executor::block_on(
+			rpc.get_program_accounts_pages(
+				&Pubkey::new_unique(),
+				RpcProgramAccountsConfig::default(),
+				0,
+			)
+			.try_collect::<Vec<_>>(),
+		);
+
+		check!(let Err(ClientError::Other(_)) = result);
+		check!(provider.calls() == 0);
+	}
+}
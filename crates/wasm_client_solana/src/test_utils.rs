@@ -0,0 +1,128 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures_timer::Delay;
+use serde_json::Value;
+use serde_json::json;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::ClientError;
+use crate::ClientResult;
+use crate::LOCALNET;
+use crate::RpcError;
+use crate::RpcErrorDetails;
+use crate::RpcProvider;
+use crate::SolanaRpcClient;
+
+type Handler = dyn Fn(&'static str, &Value) -> ClientResult<Value> + Send + Sync;
+
+/// An [`RpcProvider`] for unit tests which responds with the result of its
+/// handler and records every request.
+///
+/// The handler returns the `result` of the response. A [`ClientError::Rpc`]
+/// is returned in the `error` field of the response body like a real rpc
+/// would, while every other error fails the request.
+pub(crate) struct TestProvider {
+	url: String,
+	delay: Option<Duration>,
+	handler: Box<Handler>,
+	requests: Mutex<Vec<(&'static str, Value)>>,
+}
+
+impl TestProvider {
+	pub(crate) fn new(
+		handler: impl Fn(&'static str, &Value) -> ClientResult<Value> + Send + Sync + 'static,
+	) -> Self {
+		Self {
+			url: LOCALNET.into(),
+			delay: None,
+			handler: Box::new(handler),
+			requests: Mutex::default(),
+		}
+	}
+
+	pub(crate) fn with_url(mut self, url: impl Into<String>) -> Self {
+		self.url = url.into();
+		self
+	}
+
+	/// Wait for the `delay` before responding to each request.
+	pub(crate) fn with_delay(mut self, delay: Duration) -> Self {
+		self.delay = Some(delay);
+		self
+	}
+
+	/// Create a client which sends its requests to the `provider`.
+	pub(crate) fn rpc(self: &Arc<Self>) -> SolanaRpcClient {
+		SolanaRpcClient::new_with_provider(self.clone(), CommitmentConfig::confirmed())
+	}
+
+	/// The params of every request for the `method`.
+	pub(crate) fn requests(&self, method: &str) -> Vec<Value> {
+		self.requests
+			.lock()
+			.unwrap()
+			.iter()
+			.filter(|(name, _)| *name == method)
+			.map(|(_, request)| request.clone())
+			.collect()
+	}
+
+	/// The number of requests sent through the provider.
+	pub(crate) fn calls(&self) -> usize {
+		self.requests.lock().unwrap().len()
+	}
+}
+
+#[async_trait]
+impl RpcProvider for TestProvider {
+	async fn send(&self, method: &'static str, request: Value) -> ClientResult<Value> {
+		self.requests
+			.lock()
+			.unwrap()
+			.push((method, request.clone()));
+
+		if let Some(delay) = self.delay {
+			Delay::new(delay).await;
+		}
+
+		match (self.handler)(method, &request) {
+			Ok(result) => Ok(json!({ "jsonrpc": "2.0", "result": result, "id": 0 })),
+			Err(ClientError::Rpc(error)) => {
+				Ok(serde_json::to_value(error)
+					.map_err(|error| ClientError::Other(error.to_string()))?)
+			}
+			Err(error) => Err(error),
+		}
+	}
+
+	fn url(&self) -> String {
+		self.url.clone()
+	}
+}
+
+/// An rpc error which is returned in the response body.
+pub(crate) fn rpc_error(code: i32, message: &str) -> ClientError {
+	RpcError {
+		error: RpcErrorDetails {
+			code,
+			message: message.into(),
+		},
+		..Default::default()
+	}
+	.into()
+}
+
+/// A base64 encoded system account with the `lamports`.
+pub(crate) fn account_json(lamports: u64) -> Value {
+	json!({
+		"lamports": lamports,
+		"data": ["", "base64"],
+		"owner": "11111111111111111111111111111111",
+		"executable": false,
+		"rentEpoch": 0,
+		"space": 0
+	})
+}
@@ -0,0 +1,242 @@
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::message::v0;
+use solana_sdk::pubkey::Pubkey;
+use typed_builder::TypedBuilder;
+
+use crate::ClientResult;
+use crate::MAX_TRANSACTION_SIZE;
+use crate::TransactionTooLargeError;
+use crate::VersionedMessageExtension;
+
+/// Split a long list of instructions into the fewest transactions which fit
+/// within [`MAX_TRANSACTION_SIZE`](crate::MAX_TRANSACTION_SIZE).
+///
+/// The order of the instructions is preserved and the provided address
+/// lookup tables are used to compress the accounts of each transaction.
+///
+/// ```
+/// use solana_sdk::hash::Hash;
+/// use solana_sdk::pubkey::Pubkey;
+/// use solana_sdk::system_instruction::transfer;
+/// use wasm_client_solana::ClientResult;
+/// use wasm_client_solana::TransactionPlanner;
+///
+/// # fn main() -> ClientResult<()> {
+/// let payer = Pubkey::new_unique();
+/// let instructions = (0..50)
+/// 	.map(|_| transfer(&payer, &Pubkey::new_unique(), 1))
+/// 	.collect::<Vec<_>>();
+/// let planner = TransactionPlanner::builder().payer(payer).build();
+/// let messages = planner.messages(&instructions, Hash::default())?;
+///
+/// assert!(messages.len() > 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct TransactionPlanner {
+	/// The payer of every transaction.
+	payer: Pubkey,
+	/// The address lookup tables which can be used by every transaction.
+	#[builder(default)]
+	address_lookup_tables: Vec<AddressLookupTableAccount>,
+	/// Instructions which are added to the start of every transaction, e.g.
+	/// compute budget instructions.
+	#[builder(default)]
+	prefix_instructions: Vec<Instruction>,
+}
+
+impl TransactionPlanner {
+	/// Group the `instructions` into the fewest transactions that fit. The
+	/// prefix instructions are not included in the returned groups.
+	///
+	/// An error is returned when a single instruction doesn't fit into a
+	/// transaction.
+	pub fn plan(&self, instructions: &[Instruction]) -> ClientResult<Vec<Vec<Instruction>>> {
+		let mut groups: Vec<Vec<Instruction>> = vec![];
+		let mut current: Vec<Instruction> = vec![];
+
+		for instruction in instructions {
+			current.push(instruction.clone());
+
+			if self.transaction_size(&current)? <= MAX_TRANSACTION_SIZE {
+				continue;
+			}
+
+			// start a new transaction with the instruction which didn't fit
+			if current.len() > 1 {
+				current.pop();
+				groups.push(std::mem::take(&mut current));
+				current.push(instruction.clone());
+			}
+
+			let size = self.transaction_size(&current)?;
+
+			if size > MAX_TRANSACTION_SIZE {
+				return Err(TransactionTooLargeError {
+					size,
+					excess: size - MAX_TRANSACTION_SIZE,
+				}
+				.into());
+			}
+		}
+
+		if !current.is_empty() {
+			groups.push(current);
+		}
+
+		Ok(groups)
+	}
+
+	/// Group the `instructions` into the fewest transactions that fit and
+	/// compile the message for each transaction, including the prefix
+	/// instructions.
+	pub fn messages(
+		&self,
+		instructions: &[Instruction],
+		recent_blockhash: Hash,
+	) -> ClientResult<Vec<VersionedMessage>> {
+		self.plan(instructions)?
+			.iter()
+			.map(|group| self.compile(group, recent_blockhash))
+			.collect()
+	}
+
+	fn compile(
+		&self,
+		instructions: &[Instruction],
+		recent_blockhash: Hash,
+	) -> ClientResult<VersionedMessage> {
+		let instructions = [self.prefix_instructions.as_slice(), instructions].concat();
+		let message = v0::Message::try_compile(
+			&self.payer,
+			&instructions,
+			&self.address_lookup_tables,
+			recent_blockhash,
+		)?;
+
+		Ok(VersionedMessage::V0(message))
+	}
+
+	/// The size of the blockhash doesn't change so the default is used while
+	/// planning.
+	fn transaction_size(&self, instructions: &[Instruction]) -> ClientResult<usize> {
+		let message = self.compile(instructions, Hash::default())?;
+
+		Ok(message.transaction_size())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+	use solana_sdk::system_instruction::transfer;
+	use solana_sdk::transaction::VersionedTransaction;
+
+	use super::*;
+	use crate::ClientError;
+	use crate::VersionedTransactionExtension;
+
+	fn transfers(payer: &Pubkey, count: usize) -> Vec<Instruction> {
+		(0..count)
+			.map(|_| transfer(payer, &Pubkey::new_unique(), 1))
+			.collect()
+	}
+
+	#[test]
+	fn transaction_size_matches_serialized_size() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let message = VersionedMessage::V0(v0::Message::try_compile(
+			&payer,
+			&transfers(&payer, 3),
+			&[],
+			Hash::default(),
+		)?);
+		let transaction = VersionedTransaction::new_unsigned(message.clone());
+
+		check!(message.transaction_size() == bincode::serialize(&transaction)?.len());
+
+		Ok(())
+	}
+
+	#[test]
+	fn reports_excess_bytes() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let message = VersionedMessage::V0(v0::Message::try_compile(
+			&payer,
+			&transfers(&payer, 40),
+			&[],
+			Hash::default(),
+		)?);
+		let size = message.transaction_size();
+
+		check!(
+			message.check_transaction_size()
+				== Err(TransactionTooLargeError {
+					size,
+					excess: size - MAX_TRANSACTION_SIZE,
+				})
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn splits_into_fewest_transactions() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let instructions = transfers(&payer, 60);
+		let planner = TransactionPlanner::builder().payer(payer).build();
+		let groups = planner.plan(&instructions)?;
+
+		check!(groups.concat() == instructions);
+
+		for (index, group) in groups.iter().enumerate() {
+			let message = planner.compile(group, Hash::default())?;
+			check!(message.check_transaction_size().is_ok());
+
+			// the first instruction of the next group would not have fit
+			if let Some(next) = groups.get(index + 1) {
+				let group = [group.as_slice(), &next[..1]].concat();
+				let message = planner.compile(&group, Hash::default())?;
+				check!(message.check_transaction_size().is_err());
+			}
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn address_lookup_tables_reduce_transactions() -> anyhow::Result<()> {
+		let payer = Pubkey::new_unique();
+		let instructions = transfers(&payer, 60);
+		let addresses = instructions
+			.iter()
+			.map(|instruction| instruction.accounts[1].pubkey)
+			.collect();
+		let lookup_table = AddressLookupTableAccount {
+			key: Pubkey::new_unique(),
+			addresses,
+		};
+		let without = TransactionPlanner::builder().payer(payer).build();
+		let with = TransactionPlanner::builder()
+			.payer(payer)
+			.address_lookup_tables(vec![lookup_table])
+			.build();
+
+		check!(with.plan(&instructions)?.len() < without.plan(&instructions)?.len());
+
+		Ok(())
+	}
+
+	#[test]
+	fn instruction_too_large() {
+		let payer = Pubkey::new_unique();
+		let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), &[0; 1300], vec![]);
+		let planner = TransactionPlanner::builder().payer(payer).build();
+
+		check!(let Err(ClientError::TransactionTooLarge(_)) = planner.plan(&[instruction]));
+	}
+}
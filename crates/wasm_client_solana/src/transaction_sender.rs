@@ -0,0 +1,492 @@
+use std::time::Duration;
+
+use futures::FutureExt;
+use futures::StreamExt;
+use futures_timer::Delay;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use typed_builder::TypedBuilder;
+
+use crate::ClientResult;
+use crate::SolanaRpcClient;
+use crate::Subscription;
+use crate::TransactionConfirmation;
+use crate::rpc_config::RpcSendTransactionConfig;
+use crate::rpc_config::RpcSignatureSubscribeConfig;
+use crate::rpc_config::SignatureSubscribeRequest;
+use crate::rpc_response::RpcSignatureResult;
+use crate::rpc_response::SignatureNotificationResponse;
+use crate::solana_transaction_status::UiTransactionEncoding;
+
+/// Send a signed transaction and rebroadcast it until it is confirmed or its
+/// blockhash expires.
+///
+/// The transaction is sent with `max_retries: 0` so that the rpc node doesn't
+/// queue its own retries. Confirmation is watched with a signature
+/// subscription when the websocket is available and by polling
+/// `getSignatureStatuses` otherwise.
+///
+/// ```rust
+/// # use solana_sdk::transaction::VersionedTransaction;
+/// # use wasm_client_solana::ClientResult;
+/// use wasm_client_solana::SolanaRpcClient;
+/// use wasm_client_solana::TransactionConfirmation;
+/// use wasm_client_solana::TransactionSender;
+///
+/// # async fn run(rpc: SolanaRpcClient, transaction: VersionedTransaction, last_valid_block_height: u64) -> ClientResult<()> {
+/// let sender = TransactionSender::builder().rpc(&rpc).build();
+/// let confirmation = sender.send(&transaction, last_valid_block_height).await?;
+///
+/// if let TransactionConfirmation::Expired = confirmation {
+/// 	// rebuild the transaction with a new blockhash
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct TransactionSender {
+	/// The client used to send and confirm the transaction.
+	#[builder(setter(into))]
+	rpc: SolanaRpcClient,
+	/// The duration to wait before the transaction is sent again.
+	#[builder(default = Duration::from_secs(2))]
+	resend_interval: Duration,
+	/// The commitment the transaction must reach. Defaults to the commitment
+	/// of the `rpc`.
+	#[builder(default, setter(strip_option))]
+	commitment_config: Option<CommitmentConfig>,
+	/// Skip the preflight checks when the transaction is first sent. The
+	/// checks are always skipped when the transaction is sent again.
+	#[builder(default)]
+	skip_preflight: bool,
+	/// Watch for confirmation with a signature subscription. Confirmation is
+	/// polled when disabled or when the subscription can't be created.
+	#[builder(default = true)]
+	use_subscription: bool,
+}
+
+impl TransactionSender {
+	/// Send the signed `transaction` until it reaches the commitment or the
+	/// block height passes the `last_valid_block_height` of its blockhash.
+	pub async fn send(
+		&self,
+		transaction: &VersionedTransaction,
+		last_valid_block_height: u64,
+	) -> ClientResult<TransactionConfirmation> {
+		let commitment_config = self
+			.commitment_config
+			.unwrap_or_else(|| self.rpc.commitment_config());
+		let signature = self
+			.rpc
+			.send_transaction_with_config(
+				transaction,
+				Self::send_config(commitment_config, self.skip_preflight),
+			)
+			.await?;
+		let mut subscription = if self.use_subscription {
+			self.subscribe(signature, commitment_config).await
+		} else {
+			None
+		};
+		let result = self
+			.confirm(
+				transaction,
+				signature,
+				last_valid_block_height,
+				commitment_config,
+				&mut subscription,
+			)
+			.await;
+
+		if let Some(subscription) = subscription {
+			self.unsubscribe(signature, &subscription).await;
+		}
+
+		result
+	}
+
+	/// Resend the `transaction` until it is confirmed or its blockhash
+	/// expires.
+	async fn confirm(
+		&self,
+		transaction: &VersionedTransaction,
+		signature: Signature,
+		last_valid_block_height: u64,
+		commitment_config: CommitmentConfig,
+		subscription: &mut Option<Subscription<SignatureNotificationResponse>>,
+	) -> ClientResult<TransactionConfirmation> {
+		loop {
+			if let Some(confirmation) = self.wait_for_notification(subscription).await {
+				return Ok(confirmation);
+			}
+
+			let block_height = self
+				.rpc
+				.get_block_height_with_commitment(commitment_config)
+				.await?;
+
+			// with an active subscription the status is only polled to check
+			// whether the transaction landed before the blockhash expired.
+			if subscription.is_none() || block_height > last_valid_block_height {
+				let signature_statuses = self.rpc.get_signature_statuses(&[signature]).await?;
+
+				if let Some(confirmation) = TransactionConfirmation::from_status(
+					signature_statuses.first().and_then(Option::as_ref),
+					commitment_config,
+					block_height,
+					last_valid_block_height,
+				) {
+					return Ok(confirmation);
+				}
+			}
+
+			if let Err(error) = self
+				.rpc
+				.send_transaction_with_config(
+					transaction,
+					Self::send_config(commitment_config, true),
+				)
+				.await
+			{
+				log::debug!("failed to resend transaction {signature}: {error}");
+			}
+		}
+	}
+
+	fn send_config(
+		commitment_config: CommitmentConfig,
+		skip_preflight: bool,
+	) -> RpcSendTransactionConfig {
+		RpcSendTransactionConfig {
+			skip_preflight,
+			preflight_commitment: Some(commitment_config.commitment),
+			encoding: Some(UiTransactionEncoding::Base64),
+			max_retries: Some(0),
+			..Default::default()
+		}
+	}
+
+	async fn subscribe(
+		&self,
+		signature: Signature,
+		commitment_config: CommitmentConfig,
+	) -> Option<Subscription<SignatureNotificationResponse>> {
+		let request = SignatureSubscribeRequest::builder()
+			.signature(signature)
+			.config(
+				RpcSignatureSubscribeConfig::builder()
+					.commitment(commitment_config)
+					.build(),
+			)
+			.build();
+
+		match self.rpc.signature_subscribe(request).await {
+			Ok(subscription) => Some(subscription),
+			Err(error) => {
+				log::debug!("polling for confirmation of {signature}: {error}");
+				None
+			}
+		}
+	}
+
+	/// Remove the `subscription` unless the rpc doesn't respond within the
+	/// resend interval.
+	async fn unsubscribe(
+		&self,
+		signature: Signature,
+		subscription: &Subscription<SignatureNotificationResponse>,
+	) {
+		let result = futures::select! {
+			result = subscription.unsubscribe().fuse() => result,
+			() = Delay::new(self.resend_interval).fuse() => return,
+		};
+
+		if let Err(error) = result {
+			log::debug!("failed to remove the subscription for {signature}: {error}");
+		}
+	}
+
+	/// Wait for the resend interval or a notification from the
+	/// `subscription`. The `subscription` is removed if it ends so that the
+	/// confirmation is polled instead. It is also removed once the
+	/// transaction was processed since the rpc removes the subscription after
+	/// its notification.
+	async fn wait_for_notification(
+		&self,
+		subscription: &mut Option<Subscription<SignatureNotificationResponse>>,
+	) -> Option<TransactionConfirmation> {
+		let Some(stream) = subscription.as_mut() else {
+			Delay::new(self.resend_interval).await;
+			return None;
+		};
+
+		let notification = futures::select! {
+			notification = stream.next().fuse() => notification,
+			() = Delay::new(self.resend_interval).fuse() => return None,
+		};
+
+		match notification.map(|notification| notification.params.result.value) {
+			Some(RpcSignatureResult::ProcessedSignature(result)) => {
+				*subscription = None;
+
+				Some(match result.err {
+					Some(error) => TransactionConfirmation::Failed(error),
+					None => TransactionConfirmation::Confirmed,
+				})
+			}
+			Some(RpcSignatureResult::ReceivedSignature(_)) => None,
+			None => {
+				*subscription = None;
+				None
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	#[cfg(feature = "ssr")]
+	use std::sync::Mutex;
+	use std::sync::atomic::AtomicU64;
+	use std::sync::atomic::Ordering;
+
+	use assert2::check;
+	use serde_json::Value;
+	use serde_json::json;
+	use solana_sdk::hash::Hash;
+	use solana_sdk::pubkey::Pubkey;
+	use solana_sdk::signature::Keypair;
+	use solana_sdk::signer::Signer;
+	use solana_sdk::system_instruction::transfer;
+
+	use super::*;
+	#[cfg(feature = "ssr")]
+	use crate::ClientError;
+	#[cfg(feature = "ssr")]
+	use crate::ConnectionState;
+	#[cfg(feature = "ssr")]
+	use crate::WebSocketProvider;
+	use crate::prelude::*;
+	use crate::test_utils::TestProvider;
+	#[cfg(feature = "ssr")]
+	use crate::test_utils::rpc_error;
+
+	fn signed_transaction() -> VersionedTransaction {
+		let payer = Keypair::new();
+		let instruction = transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+		let mut transaction = VersionedTransaction::new_unsigned_v0(
+			&payer.pubkey(),
+			&[instruction],
+			&[],
+			Hash::default(),
+		)
+		.unwrap();
+		transaction.try_sign(&[&payer], None).unwrap();
+
+		transaction
+	}
+
+	/// Confirms the transaction once it was sent `confirm_after` times and
+	/// increases the block height with every request for it.
+	fn setup(confirm_after: u64) -> (Arc<TestProvider>, TransactionSender, VersionedTransaction) {
+		let transaction = signed_transaction();
+		let signature = transaction.signatures[0];
+		let sends = Arc::new(AtomicU64::new(0));
+		let block_height = AtomicU64::new(0);
+		let provider = Arc::new(TestProvider::new(move |method, _| {
+			let result = match method {
+				"sendTransaction" => {
+					sends.fetch_add(1, Ordering::SeqCst);
+					json!(signature.to_string())
+				}
+				"getBlockHeight" => json!(block_height.fetch_add(1, Ordering::SeqCst)),
+				"getSignatureStatuses" => {
+					let confirmed = sends.load(Ordering::SeqCst) >= confirm_after;
+					let status = confirmed.then(|| {
+						json!({
+							"slot": 1,
+							"confirmations": null,
+							"err": null,
+							"confirmationStatus": "finalized"
+						})
+					});
+
+					json!({ "context": { "slot": 1 }, "value": [status] })
+				}
+				_ => Value::Null,
+			};
+
+			Ok(result)
+		}));
+		let rpc =
+			SolanaRpcClient::new_with_provider(provider.clone(), CommitmentConfig::finalized());
+		let sender = TransactionSender::builder()
+			.rpc(rpc)
+			.resend_interval(Duration::ZERO)
+			.use_subscription(false)
+			.build();
+
+		(provider, sender, transaction)
+	}
+
+	#[test]
+	fn resends_until_confirmed() -> anyhow::Result<()> {
+		let (provider, sender, transaction) = setup(3);
+		let confirmation = futures::executor::block_on(sender.send(&transaction, 100))?;
+
+		check!(confirmation == TransactionConfirmation::Confirmed);
+		check!(provider.requests("sendTransaction").len() == 3);
+
+		Ok(())
+	}
+
+	#[test]
+	fn stops_when_blockhash_expires() -> anyhow::Result<()> {
+		let (provider, sender, transaction) = setup(u64::MAX);
+		let confirmation = futures::executor::block_on(sender.send(&transaction, 5))?;
+
+		check!(confirmation == TransactionConfirmation::Expired);
+		// the initial send and a resend for each block height up to 5
+		check!(provider.requests("sendTransaction").len() == 7);
+
+		Ok(())
+	}
+
+	/// Accept a single websocket connection, respond to every request and
+	/// record its method. When `notify` is set a notification that the
+	/// signature was processed is sent after subscribing.
+	#[cfg(feature = "ssr")]
+	fn websocket_server(
+		notify: bool,
+	) -> anyhow::Result<(WebSocketProvider, Arc<Mutex<Vec<String>>>)> {
+		use async_tungstenite::tungstenite::Message;
+		use async_tungstenite::tungstenite::accept;
+
+		let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+		let provider = WebSocketProvider::new(format!("ws://{}", listener.local_addr()?));
+		let methods = Arc::<Mutex<Vec<String>>>::default();
+		let recorded = methods.clone();
+
+		std::thread::spawn(move || {
+			let (stream, _) = listener.accept().unwrap();
+			let mut websocket = accept(stream).unwrap();
+
+			// the connection is closed once the sender drops the subscription
+			while let Ok(message) = websocket.read() {
+				let Ok(request) =
+					serde_json::from_str::<Value>(message.to_text().unwrap_or_default())
+				else {
+					continue;
+				};
+				let method = request["method"].as_str().unwrap_or_default().to_string();
+				let result = if method == "signatureSubscribe" {
+					json!(7)
+				} else {
+					json!(true)
+				};
+				recorded.lock().unwrap().push(method.clone());
+
+				let response = json!({ "jsonrpc": "2.0", "result": result, "id": request["id"] });
+				websocket.send(Message::text(response.to_string())).unwrap();
+
+				if notify && method == "signatureSubscribe" {
+					let notification = json!({
+						"jsonrpc": "2.0",
+						"method": "signatureNotification",
+						"params": {
+							"result": { "context": { "slot": 1 }, "value": { "err": null } },
+							"subscription": 7
+						}
+					});
+					websocket
+						.send(Message::text(notification.to_string()))
+						.unwrap();
+				}
+			}
+		});
+
+		Ok((provider, methods))
+	}
+
+	/// Send a transaction whose blockhash is valid up to block height 5 while
+	/// watching it with a signature subscription. Returns the result along
+	/// with the websocket methods which were called and the state of the
+	/// connection afterwards.
+	#[cfg(feature = "ssr")]
+	fn send_with_subscription(
+		notify: bool,
+		handler: impl Fn(&'static str) -> ClientResult<Value> + Send + Sync + 'static,
+	) -> anyhow::Result<(
+		ClientResult<TransactionConfirmation>,
+		Vec<String>,
+		ConnectionState,
+	)> {
+		let transaction = signed_transaction();
+		let signature = transaction.signatures[0];
+		let provider = Arc::new(TestProvider::new(move |method, _| {
+			match method {
+				"sendTransaction" => Ok(json!(signature.to_string())),
+				_ => handler(method),
+			}
+		}));
+		let (ws, methods) = websocket_server(notify)?;
+		let rpc = SolanaRpcClient::new_with_providers(provider, ws, CommitmentConfig::finalized());
+		let sender = TransactionSender::builder()
+			.rpc(rpc.clone())
+			.resend_interval(Duration::from_millis(100))
+			.build();
+		let runtime = tokio::runtime::Builder::new_current_thread()
+			.enable_all()
+			.build()?;
+
+		let result = runtime.block_on(sender.send(&transaction, 5));
+		let methods = methods.lock().unwrap().clone();
+
+		Ok((result, methods, rpc.ws().connection_state()))
+	}
+
+	#[cfg(feature = "ssr")]
+	#[test]
+	fn subscription_is_removed_by_the_rpc_on_confirmation() -> anyhow::Result<()> {
+		let (result, methods, state) = send_with_subscription(true, |_| Ok(json!(0)))?;
+
+		check!(let Ok(TransactionConfirmation::Confirmed) = result);
+		// the rpc removes the subscription after its notification
+		check!(methods == ["signatureSubscribe"]);
+		check!(state == ConnectionState::Closed);
+
+		Ok(())
+	}
+
+	#[cfg(feature = "ssr")]
+	#[test]
+	fn subscription_is_removed_when_blockhash_expires() -> anyhow::Result<()> {
+		let (result, methods, state) = send_with_subscription(false, |method| {
+			Ok(match method {
+				"getBlockHeight" => json!(6),
+				_ => json!({ "context": { "slot": 1 }, "value": [null] }),
+			})
+		})?;
+
+		check!(let Ok(TransactionConfirmation::Expired) = result);
+		check!(methods == ["signatureSubscribe", "signatureUnsubscribe"]);
+		check!(state == ConnectionState::Closed);
+
+		Ok(())
+	}
+
+	#[cfg(feature = "ssr")]
+	#[test]
+	fn subscription_is_removed_on_error() -> anyhow::Result<()> {
+		let (result, methods, state) =
+			send_with_subscription(false, |_| Err(rpc_error(-32005, "Node is behind")))?;
+
+		check!(let Err(ClientError::Rpc(_)) = result);
+		check!(methods == ["signatureSubscribe", "signatureUnsubscribe"]);
+		check!(state == ConnectionState::Closed);
+
+		Ok(())
+	}
+}
@@ -1,4 +1,7 @@
 use std::future::Future;
+#[cfg(not(target_arch = "wasm32"))]
+use std::hash::BuildHasher;
+use std::time::Duration;
 
 pub fn spawn_local<F>(fut: F)
 where
@@ -42,3 +45,46 @@ pub(crate) fn get_ws_url(url: impl Into<String>) -> String {
 
 	url
 }
+
+/// The delay for the zero based `attempt` of an exponential backoff.
+pub(crate) fn exponential_delay(
+	initial_delay: Duration,
+	multiplier: f64,
+	max_delay: Duration,
+	attempt: u32,
+) -> Duration {
+	let exponent = i32::try_from(attempt).unwrap_or(i32::MAX);
+	let delay = initial_delay.as_secs_f64() * multiplier.powi(exponent);
+
+	// large finite delays overflow `Duration` long before they become infinite
+	Duration::try_from_secs_f64(delay).map_or(max_delay, |delay| delay.min(max_delay))
+}
+
+/// A random number in the range `[0, 1)` for jitter. `Math.random()` is used
+/// on `wasm` where the keys of `RandomState` are fixed and every call would
+/// return the same value.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn random_fraction() -> f64 {
+	cfg_if::cfg_if! {
+		if #[cfg(target_arch = "wasm32")] {
+			js_sys::Math::random()
+		} else {
+			let value = std::collections::hash_map::RandomState::new().hash_one(0u8);
+			(value >> 11) as f64 / (1u64 << 53) as f64
+		}
+	}
+}
+
+/// The current time since the unix epoch. `Date.now()` is used on `wasm`
+/// where `SystemTime` is not available.
+pub(crate) fn now() -> Duration {
+	cfg_if::cfg_if! {
+		if #[cfg(target_arch = "wasm32")] {
+			Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+		} else {
+			std::time::SystemTime::now()
+				.duration_since(std::time::UNIX_EPOCH)
+				.unwrap_or_default()
+		}
+	}
+}
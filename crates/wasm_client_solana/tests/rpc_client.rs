@@ -9,6 +9,7 @@ use solana_sdk::native_token::sol_to_lamports;
 use solana_sdk::signature::Keypair;
 use test_utils_keypairs::get_wallet_keypair;
 use wasm_bindgen_test::*;
+use wasm_client_solana::AccountStore;
 use wasm_client_solana::LOCALNET;
 use wasm_client_solana::SolanaRpcClient;
 use wasm_client_solana::prelude::*;
@@ -137,3 +138,31 @@ pub async fn account_subscription() -> Result<()> {
 
 	Ok(())
 }
+
+#[wasm_bindgen_test]
+pub async fn account_store() -> Result<()> {
+	let rpc = SolanaRpcClient::new(LOCALNET);
+	let store = AccountStore::new(&rpc);
+	let pubkey = Keypair::new().pubkey();
+	let mut first = store.watch(pubkey).await?;
+	let second = store.watch(pubkey).await?;
+
+	check!(store.watched() == vec![pubkey]);
+	check!(first.next().await == Some(None));
+
+	let lamports = sol_to_lamports(1.0);
+	let signature = rpc.request_airdrop(&pubkey, lamports).await?;
+	rpc.confirm_transaction(&signature).await?;
+
+	let account = first.next().await.flatten();
+	check!(account.map(|account| account.lamports) == Some(lamports));
+	check!(second.current().map(|account| account.lamports) == Some(lamports));
+
+	drop(first);
+	check!(store.watched() == vec![pubkey]);
+
+	drop(second);
+	check!(store.watched().is_empty());
+
+	Ok(())
+}